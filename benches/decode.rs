@@ -0,0 +1,90 @@
+//! Decode benchmark over large arrays and lists, like heightmaps and block states in chunks.
+//!
+//! Run with `cargo bench`. Reports time per parse and allocator calls, so missing reservations show up as reallocations.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use raw_nbt::decode::{from_slice, Parser};
+use raw_nbt::encode::Encoder;
+use raw_nbt::{Compound, Value};
+
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ITERATIONS: u32 = 50;
+
+fn main() {
+    let bytes = sample();
+
+    bench("Parser::parse", &bytes, |bs| Parser::new(bs).parse().unwrap());
+    bench("from_slice", &bytes, |bs| from_slice(bs).unwrap());
+}
+
+fn bench(name: &str, bytes: &[u8], f: impl Fn(&[u8]) -> Value) {
+    black_box(f(bytes));
+
+    let allocs = ALLOCS.load(Ordering::Relaxed);
+    let reallocs = REALLOCS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f(black_box(bytes)));
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    let allocs = (ALLOCS.load(Ordering::Relaxed) - allocs) / ITERATIONS as usize;
+    let reallocs = (REALLOCS.load(Ordering::Relaxed) - reallocs) / ITERATIONS as usize;
+
+    println!(
+        "{:<16} {:>10.3?}/iter  {} allocs/iter  {} reallocs/iter  ({} bytes)",
+        name,
+        elapsed,
+        allocs,
+        reallocs,
+        bytes.len()
+    );
+}
+
+/// Chunk-like compound with 24 sections of block state arrays, plus big heightmaps and lists.
+fn sample() -> Vec<u8> {
+    let mut sections = Vec::new();
+    for y in 0..24 {
+        let mut section = Compound::new();
+        section.insert("Y".to_string(), Value::Byte(y));
+        section.insert("BlockStates".to_string(), Value::LongArray((0..4096).map(|i| i * 31 + y as i64).collect()));
+        section.insert("BlockLight".to_string(), Value::ByteArray(vec![y; 2048]));
+        section.insert("Palette".to_string(), Value::StrList((0..64).map(|i| format!("minecraft:block_{}", i)).collect()));
+        sections.push(section);
+    }
+
+    let mut root = Compound::new();
+    root.insert("Sections".to_string(), Value::CompoundList(sections));
+    root.insert("Heightmap".to_string(), Value::IntArray((0..65536).collect()));
+    root.insert("Positions".to_string(), Value::DoubleList((0..65536).map(f64::from).collect()));
+
+    let mut enc = Encoder::new(Vec::new());
+    enc.write("", &Value::Compound(root)).unwrap();
+    enc.into_inner()
+}
@@ -0,0 +1,142 @@
+//! Packing and unpacking of palette indices packed into long arrays, like chunk `BlockStates`.
+//!
+//! ```
+//! use raw_nbt::blockstate::unpack_block_states;
+//!
+//! // 5 bits per entry: 12 entries fit in each long.
+//! let data = [0x0000_0000_0000_0021, 0x0000_0000_0000_0003];
+//! assert_eq!(unpack_block_states(&data, 5, 13).unwrap()[..3], [1, 1, 0]);
+//! ```
+//!
+//! To write modified entries back, pack them with the layout of the data version:
+//!
+//! ```
+//! use raw_nbt::blockstate::{pack_block_states, unpack_with_layout, Layout};
+//!
+//! let entries = [3, 0, 7, 1];
+//! let data = pack_block_states(&entries, 4, Layout::Padded).unwrap();
+//! assert_eq!(unpack_with_layout(&data, 4, 4, Layout::Padded).unwrap(), entries);
+//! ```
+
+use alloc::vec;
+
+use crate::*;
+
+/// How entries are laid out in longs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Entries are packed without gaps and may span two longs. Used before Minecraft 1.16.
+    Spanning,
+    /// Each long holds `64 / bits_per_entry` entries and the remaining high bits are unused. Used since 1.16.
+    Padded,
+}
+
+impl Layout {
+    /// Number of longs needed for `count` entries.
+    pub fn long_count(self, bits_per_entry: usize, count: usize) -> usize {
+        match self {
+            Layout::Spanning => (count * bits_per_entry).div_ceil(64),
+            Layout::Padded => {
+                let per_long = 64 / bits_per_entry;
+                count.div_ceil(per_long)
+            }
+        }
+    }
+}
+
+/// Unpack `count` entries of `bits_per_entry` bits, detecting the layout by length of `data`.
+///
+/// If both layouts have the same length, `Layout::Padded` is used.
+/// Returns `Error::InvalidLength` with the padded length if neither matches,
+/// and `Error::Overflow` if `bits_per_entry` is not in `1..=16`.
+pub fn unpack_block_states(data: &[i64], bits_per_entry: usize, count: usize) -> Result<Vec<u16>> {
+    check_bits(bits_per_entry)?;
+
+    let padded = Layout::Padded.long_count(bits_per_entry, count);
+    let layout = if data.len() == padded {
+        Layout::Padded
+    } else if data.len() == Layout::Spanning.long_count(bits_per_entry, count) {
+        Layout::Spanning
+    } else {
+        return Err(Error::InvalidLength(padded, data.len()));
+    };
+
+    unpack_with_layout(data, bits_per_entry, count, layout)
+}
+
+/// Unpack `count` entries of `bits_per_entry` bits in `layout`.
+///
+/// Returns `Error::InvalidLength` if `data` is too short. Extra longs are ignored.
+/// Returns `Error::Overflow` if `bits_per_entry` is not in `1..=16`.
+pub fn unpack_with_layout(data: &[i64], bits_per_entry: usize, count: usize, layout: Layout) -> Result<Vec<u16>> {
+    check_bits(bits_per_entry)?;
+
+    let needed = layout.long_count(bits_per_entry, count);
+    if data.len() < needed {
+        return Err(Error::InvalidLength(needed, data.len()));
+    }
+
+    let mask = (1u64 << bits_per_entry) - 1;
+    let mut entries = Vec::with_capacity(count);
+
+    match layout {
+        Layout::Spanning => {
+            for i in 0..count {
+                let bit = i * bits_per_entry;
+                let (index, offset) = (bit / 64, bit % 64);
+                let mut x = data[index] as u64 >> offset;
+                if offset + bits_per_entry > 64 {
+                    x |= (data[index + 1] as u64) << (64 - offset);
+                }
+                entries.push((x & mask) as u16);
+            }
+        }
+        Layout::Padded => {
+            let per_long = 64 / bits_per_entry;
+            for i in 0..count {
+                let x = data[i / per_long] as u64 >> (i % per_long * bits_per_entry);
+                entries.push((x & mask) as u16);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Pack `entries` of `bits_per_entry` bits into longs in `layout`, reversing `unpack_with_layout`.
+///
+/// Returns `Error::Overflow` if `bits_per_entry` is not in `1..=16` or an entry doesn't fit in it.
+/// Unused bits are zero.
+pub fn pack_block_states(entries: &[u16], bits_per_entry: usize, layout: Layout) -> Result<Vec<i64>> {
+    check_bits(bits_per_entry)?;
+
+    let mask = (1u64 << bits_per_entry) - 1;
+    let mut data = vec![0u64; layout.long_count(bits_per_entry, entries.len())];
+    let per_long = 64 / bits_per_entry;
+
+    for (i, &entry) in entries.iter().enumerate() {
+        let x = u64::from(entry);
+        if x > mask {
+            return Err(Error::Overflow(i64::from(entry), "palette index"));
+        }
+
+        let bit = match layout {
+            Layout::Spanning => i * bits_per_entry,
+            Layout::Padded => i / per_long * 64 + i % per_long * bits_per_entry,
+        };
+        let (index, offset) = (bit / 64, bit % 64);
+        data[index] |= x << offset;
+        if offset + bits_per_entry > 64 {
+            data[index + 1] |= x >> (64 - offset);
+        }
+    }
+
+    Ok(data.into_iter().map(|x| x as i64).collect())
+}
+
+fn check_bits(bits_per_entry: usize) -> Result<()> {
+    if !(1..=16).contains(&bits_per_entry) {
+        return Err(Error::Overflow(bits_per_entry as i64, "bits per entry"));
+    }
+    Ok(())
+}
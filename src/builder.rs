@@ -0,0 +1,96 @@
+use crate::*;
+
+/// Fluent builder for `Value::Compound`.
+///
+/// ```
+/// use raw_nbt::CompoundBuilder;
+///
+/// let pig = CompoundBuilder::new()
+///     .byte("OnGround", 1)
+///     .string("id", "minecraft:pig")
+///     .list("Pos", vec![0.5f64, 64.0, 0.5])
+///     .build();
+/// assert_eq!(pig["id"].str().unwrap(), "minecraft:pig");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CompoundBuilder {
+    entries: Compound,
+}
+
+impl CompoundBuilder {
+    pub fn new() -> CompoundBuilder {
+        CompoundBuilder::default()
+    }
+
+    /// Set `name` to any value. Later calls with the same name overwrite earlier ones.
+    pub fn value(mut self, name: impl Into<String>, value: impl Into<Value>) -> CompoundBuilder {
+        self.entries.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn byte(self, name: impl Into<String>, x: i8) -> CompoundBuilder {
+        self.value(name, Value::Byte(x))
+    }
+
+    pub fn short(self, name: impl Into<String>, x: i16) -> CompoundBuilder {
+        self.value(name, Value::Short(x))
+    }
+
+    pub fn int(self, name: impl Into<String>, x: i32) -> CompoundBuilder {
+        self.value(name, Value::Int(x))
+    }
+
+    pub fn long(self, name: impl Into<String>, x: i64) -> CompoundBuilder {
+        self.value(name, Value::Long(x))
+    }
+
+    pub fn float(self, name: impl Into<String>, x: f32) -> CompoundBuilder {
+        self.value(name, Value::Float(x))
+    }
+
+    pub fn double(self, name: impl Into<String>, x: f64) -> CompoundBuilder {
+        self.value(name, Value::Double(x))
+    }
+
+    pub fn string(self, name: impl Into<String>, x: impl Into<String>) -> CompoundBuilder {
+        self.value(name, Value::Str(x.into()))
+    }
+
+    pub fn byte_array(self, name: impl Into<String>, xs: Vec<i8>) -> CompoundBuilder {
+        self.value(name, Value::ByteArray(xs))
+    }
+
+    pub fn int_array(self, name: impl Into<String>, xs: Vec<i32>) -> CompoundBuilder {
+        self.value(name, Value::IntArray(xs))
+    }
+
+    pub fn long_array(self, name: impl Into<String>, xs: Vec<i64>) -> CompoundBuilder {
+        self.value(name, Value::LongArray(xs))
+    }
+
+    /// Set `name` to a nested compound.
+    pub fn compound(self, name: impl Into<String>, builder: CompoundBuilder) -> CompoundBuilder {
+        self.value(name, builder.build())
+    }
+
+    /// Set `name` to a list built from `items`. An empty iterator gives `Value::EndList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the items are not all of the same tag type.
+    pub fn list<T: Into<Value>>(self, name: impl Into<String>, items: impl IntoIterator<Item = T>) -> CompoundBuilder {
+        let name = name.into();
+        let elements = items.into_iter().map(Into::into).collect();
+        match Value::make_list(elements) {
+            Ok(list) => self.value(name, list),
+            Err(Error::ListTypeMismatch { index, .. }) => {
+                panic!("list '{}' has mismatched element type at index {}", name, index)
+            }
+            Err(e) => panic!("list '{}': {}", name, e),
+        }
+    }
+
+    pub fn build(self) -> Value {
+        Value::Compound(self.entries)
+    }
+}
@@ -0,0 +1,57 @@
+//! Helpers for common structures of Java Edition save data.
+//!
+//! ```
+//! use raw_nbt::common::parse_items;
+//! use raw_nbt::decode::from_snbt;
+//!
+//! let player = from_snbt(r#"{Inventory:[{Slot:0b,id:"minecraft:stone",Count:64b}]}"#).unwrap();
+//! let items = parse_items(&player["Inventory"]).unwrap();
+//! assert_eq!(items[0].id, "minecraft:stone");
+//! assert_eq!(items[0].count, 64);
+//! ```
+
+use crate::*;
+
+/// An item stack in an inventory, like `Inventory` of players or `Items` of chests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    /// `id`, like `minecraft:stone`.
+    pub id: String,
+    /// `Count` byte, or `count` int since 1.20.5. 1 if neither exists, as 1.20.5 omits a count of 1.
+    pub count: i32,
+    /// `Slot`. Missing for items not in a slot, like ones in a bundle.
+    pub slot: Option<i8>,
+    /// `tag`, with enchantments, display name, and so on. Replaced by `components` in 1.20.5.
+    pub tag: Option<Compound>,
+    /// Other entries of the item compound, like `components` or data of mods.
+    pub raw: Compound,
+}
+
+/// Read a list of item compounds.
+///
+/// Fails with `Error::InvalidType` if `value` is not a list of compounds or a known entry has another type,
+/// and with `Error::NotFound` if an item has no `id`. An empty list of any element type gives no items.
+pub fn parse_items(value: &Value) -> Result<Vec<Item>> {
+    value.compound_list()?.iter().map(|x| parse_item(x.clone())).collect()
+}
+
+fn parse_item(mut raw: Compound) -> Result<Item> {
+    let id = raw.remove("id").ok_or_else(|| Error::NotFound("id".to_string()))?.into_str()?;
+    let count = if let Some(x) = raw.remove("Count") {
+        i32::from(x.byte()?)
+    } else if let Some(x) = raw.remove("count") {
+        x.int()?
+    } else {
+        1
+    };
+    let slot = raw.remove("Slot").map(|x| x.byte()).transpose()?;
+    let tag = raw.remove("tag").map(Value::into_compound).transpose()?;
+
+    Ok(Item {
+        id,
+        count,
+        slot,
+        tag,
+        raw,
+    })
+}
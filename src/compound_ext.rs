@@ -0,0 +1,83 @@
+use alloc::borrow::Cow;
+
+use crate::*;
+
+/// Typed getters of `Compound` entries.
+///
+/// Each getter fails with `Error::NotFound` for a missing key, or `Error::InvalidType` for another type.
+///
+/// ```
+/// use raw_nbt::decode::from_snbt;
+/// use raw_nbt::CompoundExt;
+///
+/// let player = from_snbt("{Health:20.0f,abilities:{flying:1b}}").unwrap();
+/// let player = player.compound().unwrap();
+/// assert_eq!(player.get_float("Health").unwrap(), 20.0);
+/// assert_eq!(player.get_compound("abilities").unwrap().get_byte("flying").unwrap(), 1);
+/// ```
+pub trait CompoundExt {
+    /// Get entry of any type.
+    fn get_value(&self, key: &str) -> Result<&Value>;
+
+    fn get_byte(&self, key: &str) -> Result<i8> {
+        self.get_value(key)?.byte()
+    }
+
+    fn get_bool(&self, key: &str) -> Result<bool> {
+        self.get_value(key)?.bool()
+    }
+
+    fn get_short(&self, key: &str) -> Result<i16> {
+        self.get_value(key)?.short()
+    }
+
+    fn get_int(&self, key: &str) -> Result<i32> {
+        self.get_value(key)?.int()
+    }
+
+    fn get_long(&self, key: &str) -> Result<i64> {
+        self.get_value(key)?.long()
+    }
+
+    fn get_float(&self, key: &str) -> Result<f32> {
+        self.get_value(key)?.float()
+    }
+
+    fn get_double(&self, key: &str) -> Result<f64> {
+        self.get_value(key)?.double()
+    }
+
+    fn get_byte_array(&self, key: &str) -> Result<&Vec<i8>> {
+        self.get_value(key)?.byte_array()
+    }
+
+    fn get_str(&self, key: &str) -> Result<&str> {
+        self.get_value(key)?.str()
+    }
+
+    fn get_compound(&self, key: &str) -> Result<&Compound> {
+        self.get_value(key)?.compound()
+    }
+
+    fn get_int_array(&self, key: &str) -> Result<&Vec<i32>> {
+        self.get_value(key)?.int_array()
+    }
+
+    fn get_long_array(&self, key: &str) -> Result<&Vec<i64>> {
+        self.get_value(key)?.long_array()
+    }
+
+    fn get_str_list(&self, key: &str) -> Result<Cow<'_, Vec<String>>> {
+        self.get_value(key)?.str_list()
+    }
+
+    fn get_compound_list(&self, key: &str) -> Result<Cow<'_, Vec<Compound>>> {
+        self.get_value(key)?.compound_list()
+    }
+}
+
+impl CompoundExt for Compound {
+    fn get_value(&self, key: &str) -> Result<&Value> {
+        self.get(key).ok_or_else(|| Error::NotFound(key.to_string()))
+    }
+}
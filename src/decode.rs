@@ -1,90 +1,230 @@
-use std::convert::From;
-use std::fmt;
-use std::io;
-use std::io::Read;
-use std::string::FromUtf8Error;
+#[cfg(feature = "std")]
+use core::convert::TryInto;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
 
 use crate::*;
 
+#[cfg(feature = "async")]
+mod async_read;
+#[cfg(feature = "std")]
+mod level;
+mod slice;
+
+#[cfg(feature = "async")]
+pub use async_read::AsyncParser;
+#[cfg(feature = "std")]
+pub use level::{read_level_summary, LevelSummary};
+pub use slice::SliceParser;
+
 /// Parse NBT binary into Value.
-/// 
+///
 /// Input stream should be plain NBT binary.
 /// If you need decompress, use other crate (ex: flate2).
+#[cfg(feature = "std")]
 pub fn parse<R: Read>(r: R) -> Result<Value> {
     let mut parser = Parser::new(r);
     parser.parse()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Type {
-    End,
-    Byte,
-    Short,
-    Int,
-    Long,
-    Float,
-    Double,
-    ByteArray,
-    Str,
-    List,
-    Compound,
-    IntArray,
-    LongArray,
+/// Parse in-memory NBT binary into Value.
+pub fn from_slice(bytes: &[u8]) -> Result<Value> {
+    SliceParser::new(bytes).parse()
+}
+
+/// Parse in-memory NBT binary into the root name and Value.
+pub fn from_slice_named(bytes: &[u8]) -> Result<(String, Value)> {
+    SliceParser::new(bytes).parse_named()
+}
+
+/// Parse SNBT (stringified NBT) text into Value, like `{Health:20.0f,Pos:[0.0d,64.0d,0.0d]}`.
+///
+/// An empty list `[]` becomes `Value::EndList`.
+pub fn from_snbt(s: &str) -> Result<Value> {
+    crate::snbt::SnbtParser::new(s).parse()
+}
+
+/// Parse NBT file into Value.
+///
+/// With the `compression` feature, gzip and zlib files are decompressed as `from_reader_auto` does.
+/// Without it, the file must be plain NBT binary.
+/// Failures opening or reading the file are `ParseError::ReadError`.
+#[cfg(feature = "std")]
+pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Value> {
+    let r = io::BufReader::new(std::fs::File::open(path)?);
+
+    #[cfg(feature = "compression")]
+    return from_reader_auto(r);
+    #[cfg(not(feature = "compression"))]
+    return parse(r);
+}
+
+/// Parse NBT binary into Value, decompressing it if needed.
+///
+/// Gzip and zlib streams are detected by their first bytes.
+/// Other input is parsed as plain NBT binary.
+#[cfg(feature = "compression")]
+pub fn from_reader_auto<R: Read>(r: R) -> Result<Value> {
+    from_reader_auto_limited(r, u64::MAX)
+}
+
+/// Parse like `from_reader_auto`, reading at most `max_decompressed` bytes after decompression.
+///
+/// Fails with `ParseError::DecompressedSizeExceeded` as soon as the decompressed stream goes over the limit,
+/// so a small compressed upload can't expand without bound. Plain input is limited in the same way.
+#[cfg(feature = "compression")]
+pub fn from_reader_auto_limited<R: Read>(mut r: R, max_decompressed: u64) -> Result<Value> {
+    use flate2::read::{GzDecoder, ZlibDecoder};
+
+    let mut head = [0u8; 2];
+    let mut n = 0;
+    while n < head.len() {
+        match r.read(&mut head[n..]) {
+            Ok(0) => break,
+            Ok(m) => n += m,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(ParseError::from(e)),
+        }
+    }
+
+    let r = (&head[..n]).chain(r);
+    match head[..n] {
+        [0x1F, 0x8B] => parse_limited(GzDecoder::new(r), max_decompressed),
+        [0x78, _] => parse_limited(ZlibDecoder::new(r), max_decompressed),
+        _ => parse_limited(r, max_decompressed),
+    }
+}
+
+#[cfg(feature = "compression")]
+fn parse_limited<R: Read>(r: R, limit: u64) -> Result<Value> {
+    let mut r = LimitedReader {
+        r,
+        left: limit,
+        exceeded: false,
+    };
+    let value = parse(&mut r);
+
+    if r.exceeded {
+        return Err(ParseError::DecompressedSizeExceeded(limit));
+    }
+    value
+}
+
+/// Reader failing once more than `left` bytes are read, unlike `io::Take` that just ends.
+#[cfg(feature = "compression")]
+struct LimitedReader<R> {
+    r: R,
+    left: u64,
+    exceeded: bool,
 }
 
-impl Type {
-    fn try_from(byte: u8) -> Result<Type> {
-        match byte {
-            0 => Ok(Type::End),
-            1 => Ok(Type::Byte),
-            2 => Ok(Type::Short),
-            3 => Ok(Type::Int),
-            4 => Ok(Type::Long),
-            5 => Ok(Type::Float),
-            6 => Ok(Type::Double),
-            7 => Ok(Type::ByteArray),
-            8 => Ok(Type::Str),
-            9 => Ok(Type::List),
-            10 => Ok(Type::Compound),
-            11 => Ok(Type::IntArray),
-            12 => Ok(Type::LongArray),
-            b => Err(ParseError::UnknownTag(b)),
+#[cfg(feature = "compression")]
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.left == 0 {
+            if self.r.read(&mut [0u8; 1])? == 0 {
+                return Ok(0);
+            }
+            self.exceeded = true;
+            return Err(io::Error::other("decompressed size limit exceeded"));
         }
+
+        let max = buf.len().min(self.left.try_into().unwrap_or(usize::MAX));
+        let n = self.r.read(&mut buf[..max])?;
+        self.left -= n as u64;
+        Ok(n)
     }
 }
 
 /// NBT Parse error.
 #[derive(Debug)]
 pub enum ParseError {
-    InvalidUTF8(FromUtf8Error),
+    /// Input decompressed to more bytes than the limit of `from_reader_auto_limited`.
+    DecompressedSizeExceeded(u64),
+    DepthLimitExceeded(usize),
+    /// String at `offset` is not valid modified UTF-8 (nor plain UTF-8).
+    InvalidString { offset: u64 },
+    LengthLimitExceeded { declared: usize, limit: usize },
+    /// Element `index` of an SNBT list at `offset` has a different type from the first element, `declared`.
+    ///
+    /// Binary lists declare one element tag in the header, so elements can't have another type there.
+    ListTypeMismatch { declared: TagType, index: usize, offset: u64 },
+    /// `Value::parse_inner_snbt` was called on a value of this type instead of a string.
+    NotStr(TagType),
+    /// Total bytes of decoded arrays, lists, and strings exceeded the limit of `Parser::max_total_bytes`.
+    SizeLimitExceeded(usize),
+    #[cfg(feature = "std")]
     ReadError(io::Error),
+    /// Root tag is not a compound, and `allow_any_root` of the parser is off.
+    RootNotCompound(TagType),
+    /// Byte count left after the root tag in strict mode.
+    TrailingData(u64),
     UnexpectedEndOfInput,
     UnexpectedTag,
-    UnknownTag(u8),
+    /// Invalid data found while reading.
+    ///
+    /// `found` is the unknown tag id, the id of the tag whose length is invalid, the last byte of a too long or out of range VarInt,
+    /// or the unexpected character in SNBT.
+    ///
+    /// `offset` is the position in the input where the invalid data starts.
+    Parse { offset: u64, found: u8, context: &'static str },
+}
+
+impl ParseError {
+    /// Get the tag id if parsing failed on an unknown tag id, as from a newer format version.
+    ///
+    /// Unknown tags are always an error: their payload size is not known, so they cannot be skipped.
+    pub fn unknown_tag(&self) -> Option<u8> {
+        match self {
+            ParseError::Parse {
+                found,
+                context: "tag id",
+                ..
+            } => Some(*found),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::InvalidUTF8(ref cause) => cause.fmt(f),
+            ParseError::DecompressedSizeExceeded(limit) => write!(f, "decompressed data larger than {} bytes", limit),
+            ParseError::DepthLimitExceeded(limit) => write!(f, "nesting deeper than {}", limit),
+            ParseError::InvalidString { offset } => write!(f, "invalid string at offset {}", offset),
+            ParseError::LengthLimitExceeded { declared, limit } => write!(f, "too many elements: {} (limit {})", declared, limit),
+            ParseError::ListTypeMismatch { declared, index, offset } => {
+                write!(f, "list element {} at offset {} is not {:?}", index, offset, declared)
+            }
+            ParseError::NotStr(tag) => write!(f, "value is {:?}, not string", tag),
+            ParseError::SizeLimitExceeded(limit) => write!(f, "decoded data larger than {} bytes", limit),
+            #[cfg(feature = "std")]
             ParseError::ReadError(ref cause) => cause.fmt(f),
+            ParseError::RootNotCompound(tag) => write!(f, "root tag is {:?}, not compound", tag),
+            ParseError::TrailingData(n) => write!(f, "{} bytes of trailing data after root tag", n),
             ParseError::UnexpectedEndOfInput => write!(f, "unexpected input end"),
             ParseError::UnexpectedTag => write!(f, "unexpected tag"),
-            ParseError::UnknownTag(b) => write!(f, "found unknown tag {}", b),
+            ParseError::Parse { offset, found, context } => write!(f, "invalid {} {} at offset {}", context, found, offset),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             ParseError::ReadError(ref cause) => Some(cause),
-            ParseError::InvalidUTF8(ref cause) => Some(cause),
             _ => None,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl From<io::Error> for ParseError {
     fn from(e: io::Error) -> ParseError {
         match e.kind() {
@@ -94,57 +234,319 @@ impl From<io::Error> for ParseError {
     }
 }
 
-impl From<FromUtf8Error> for ParseError {
-    fn from(e: FromUtf8Error) -> ParseError {
-        ParseError::InvalidUTF8(e)
-    }
+pub type Result<T> = core::result::Result<T, ParseError>;
+
+/// Receiver of parse events from `Parser::parse_with`.
+///
+/// Every method does nothing by default, so implementors only override the events they need.
+pub trait Visitor {
+    /// Name of the following value. Reported for the root tag and each compound entry.
+    fn key(&mut self, _name: &str) {}
+
+    fn begin_compound(&mut self) {}
+
+    fn end_compound(&mut self) {}
+
+    /// Start of a list with its element type and length.
+    ///
+    /// An element type of `TagType::End` always has length 0.
+    fn begin_list(&mut self, _element: TagType, _len: usize) {}
+
+    fn end_list(&mut self) {}
+
+    /// Scalar, string, or array value. List elements are reported one by one.
+    fn value(&mut self, _value: Value) {}
 }
 
-pub type Result<T> = std::result::Result<T, ParseError>;
+/// Default nesting limit of compounds and lists.
+pub const DEFAULT_MAX_DEPTH: usize = 512;
 
+/// Key of the tag id in placeholders made by `Parser::parse_shallow`.
+pub const SHALLOW_TAG_KEY: &str = "raw_nbt:tag";
+
+/// Key of the element count in placeholders made by `Parser::parse_shallow`.
+pub const SHALLOW_LEN_KEY: &str = "raw_nbt:len";
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    BigEndian,
+    LittleEndian,
+    VarInt,
+}
+
+/// NBT Parser.
+///
+/// Input stream should be plain NBT binary.
+#[cfg(feature = "std")]
 #[derive(Debug)]
-struct Parser<R> {
+pub struct Parser<R> {
     r: R,
+    encoding: Encoding,
+    offset: u64,
+    depth: usize,
+    max_depth: usize,
+    max_elements: usize,
+    max_total_bytes: usize,
+    total_bytes: usize,
+    shallow_depth: usize,
+    strict: bool,
+    any_root: bool,
+    scratch: Vec<u8>,
 }
 
+#[cfg(feature = "std")]
 impl<R: Read> Parser<R> {
     pub fn new(r: R) -> Parser<R> {
-        Parser { r }
+        Parser {
+            r,
+            encoding: Encoding::BigEndian,
+            offset: 0,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_elements: usize::MAX,
+            max_total_bytes: usize::MAX,
+            total_bytes: 0,
+            shallow_depth: usize::MAX,
+            strict: false,
+            any_root: false,
+            scratch: Vec::new(),
+        }
     }
 
+    /// Create parser for little-endian NBT used by Bedrock Edition.
+    pub fn new_le(r: R) -> Parser<R> {
+        Parser {
+            encoding: Encoding::LittleEndian,
+            ..Parser::new(r)
+        }
+    }
+
+    /// Create parser for NBT used by Bedrock Edition network protocol.
+    ///
+    /// Int and long are zig-zag encoded VarInt, and string length is unsigned VarInt.
+    /// Other fields are little-endian.
+    pub fn new_var(r: R) -> Parser<R> {
+        Parser {
+            encoding: Encoding::VarInt,
+            ..Parser::new(r)
+        }
+    }
+
+    /// Create parser with nesting limit of compounds and lists.
+    pub fn with_max_depth(r: R, depth: usize) -> Parser<R> {
+        Parser::new(r).max_depth(depth)
+    }
+
+    /// Set nesting limit of compounds and lists.
+    ///
+    /// Parsing fails with `ParseError::DepthLimitExceeded` when the input is nested deeper.
+    pub fn max_depth(mut self, depth: usize) -> Parser<R> {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Create parser with element count limit of arrays and lists.
+    pub fn with_max_elements(r: R, max: usize) -> Parser<R> {
+        Parser::new(r).max_elements(max)
+    }
+
+    /// Set element count limit of arrays and lists.
+    ///
+    /// A declared length over the limit fails with `ParseError::LengthLimitExceeded` before allocating.
+    /// Otherwise the declared length is reserved up front, so the limit also bounds that allocation.
+    /// Unlimited by default.
+    pub fn max_elements(mut self, max: usize) -> Parser<R> {
+        self.max_elements = max;
+        self
+    }
+
+    /// Create parser with limit of total decoded bytes.
+    pub fn with_max_total_bytes(r: R, limit: usize) -> Parser<R> {
+        Parser::new(r).max_total_bytes(limit)
+    }
+
+    /// Set limit of total bytes of arrays, lists, and strings decoded since creation or `reset`.
+    ///
+    /// Going over the limit fails with `ParseError::SizeLimitExceeded` before allocating.
+    /// This bounds memory use for many moderately sized arrays, which `max_elements` alone does not.
+    /// Unlimited by default.
+    pub fn max_total_bytes(mut self, limit: usize) -> Parser<R> {
+        self.max_total_bytes = limit;
+        self
+    }
+
+    /// Require the input to end right after a single root tag.
+    ///
+    /// In strict mode, `parse`, `parse_named`, and `parse_with` read only one root tag
+    /// and fail with `ParseError::TrailingData` if any byte follows it.
+    /// This catches compressed or otherwise wrong input early. Off by default.
+    pub fn strict(mut self, strict: bool) -> Parser<R> {
+        self.strict = strict;
+        self
+    }
+
+    /// Accept root tags of any type, as some tools and the network protocol write.
+    ///
+    /// Off by default: `parse`, `parse_all`, `parse_named`, and `parse_with` fail with
+    /// `ParseError::RootNotCompound` if a root tag is not a compound.
+    pub fn allow_any_root(mut self, allow: bool) -> Parser<R> {
+        self.any_root = allow;
+        self
+    }
+
+    /// Replace the input with `r` and return the previous one.
+    ///
+    /// Settings and the internal string buffer are kept, so one parser can be reused for many small inputs.
+    /// `offset` restarts from 0.
+    pub fn reset(&mut self, r: R) -> R {
+        self.offset = 0;
+        self.depth = 0;
+        self.total_bytes = 0;
+        std::mem::replace(&mut self.r, r)
+    }
+
+    /// Parse all root tags into a compound keyed by their names.
     pub fn parse(&mut self) -> Result<Value> {
         let mut root = Compound::new();
 
         while let Some(tag) = self.read_tag()? {
+            self.check_root(tag)?;
             let name = self.read_str()?;
             let payload = self.parse_value_with_tag(tag)?;
             root.insert(name, payload);
+
+            if self.strict {
+                self.check_trailing()?;
+                break;
+            }
         }
 
         Ok(Value::Compound(root))
     }
 
+    /// Parse root tags until the input ends, returning their payloads in order.
+    ///
+    /// Unlike `parse`, root names are dropped and duplicate names are kept.
+    /// Input ending inside a tag is still an error. `strict` is ignored.
+    pub fn parse_all(&mut self) -> Result<Vec<Value>> {
+        let mut values = Vec::new();
+
+        while let Some(tag) = self.read_tag()? {
+            self.check_root(tag)?;
+            self.skip_str()?;
+            values.push(self.parse_value_with_tag(tag)?);
+        }
+
+        Ok(values)
+    }
+
+    /// Parse like `parse`, but replace compounds and lists nested `max_depth` levels deep with placeholders.
+    ///
+    /// A placeholder is a compound with `SHALLOW_TAG_KEY` (tag id as byte) and `SHALLOW_LEN_KEY` (element count as int).
+    /// The skipped bytes are still validated. The root wrapper is not counted, so `max_depth` 0 replaces each root tag.
+    ///
+    /// Placeholders for lists in a list make the outer list invalid to encode, so the result is for preview only.
+    pub fn parse_shallow(&mut self, max_depth: usize) -> Result<Value> {
+        self.shallow_depth = max_depth;
+        let value = self.parse();
+        self.shallow_depth = usize::MAX;
+        value
+    }
+
+    /// Parse a single root tag and return it with its name.
+    ///
+    /// Unlike `parse`, the root name is returned separately instead of being used as a key.
+    pub fn parse_named(&mut self) -> Result<(String, Value)> {
+        match self.read_tag()? {
+            Some(tag) => {
+                self.check_root(tag)?;
+                let name = self.read_str()?;
+                let payload = self.parse_value_with_tag(tag)?;
+                self.check_trailing()?;
+                Ok((name, payload))
+            }
+            None => Err(ParseError::UnexpectedEndOfInput),
+        }
+    }
+
+    /// Parse a single unnamed root tag, as sent by the network protocol since Java Edition 1.20.2.
+    ///
+    /// The tag id is followed directly by the payload, without the root name of the file format.
+    /// Since 1.20.3, text components are sent with non-compound roots, so use `allow_any_root` to read them.
+    pub fn parse_network(&mut self) -> Result<Value> {
+        match self.read_tag()? {
+            Some(tag) => {
+                self.check_root(tag)?;
+                let payload = self.parse_value_with_tag(tag)?;
+                self.check_trailing()?;
+                Ok(payload)
+            }
+            None => Err(ParseError::UnexpectedEndOfInput),
+        }
+    }
+
+    /// Parse a single root tag, reporting its structure to `visitor` instead of building a `Value`.
+    ///
+    /// The root name is reported by `Visitor::key` first.
+    pub fn parse_with<V: Visitor>(&mut self, visitor: &mut V) -> Result<()> {
+        match self.read_tag()? {
+            Some(tag) => {
+                self.check_root(tag)?;
+                let name = self.read_str()?;
+                visitor.key(&name);
+                self.visit_value_with_tag(tag, visitor)?;
+                self.check_trailing()
+            }
+            None => Err(ParseError::UnexpectedEndOfInput),
+        }
+    }
+
+    /// Find an entry of the root compound by name.
+    ///
+    /// Payloads of other entries are skipped without decoding.
+    /// Returns `None` if the root is not a compound or has no such entry.
+    /// Entries after the found one are left unread.
+    pub fn find(&mut self, name: &str) -> Result<Option<Value>> {
+        let tag = self.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)?;
+        self.skip_str()?;
+        if tag != TagType::Compound {
+            self.skip_with_tag(tag)?;
+            return Ok(None);
+        }
+
+        self.enter()?;
+        let found = self.find_entry(name);
+        self.leave();
+        found
+    }
+
+    /// Number of bytes read from the input so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
     //// parse ////
 
-    fn parse_value_with_tag(&mut self, tag: Type) -> Result<Value> {
+    pub(crate) fn parse_value_with_tag(&mut self, tag: TagType) -> Result<Value> {
         match tag {
-            Type::End => Err(ParseError::UnexpectedTag),
-            Type::Byte => self.parse_byte(),
-            Type::Short => self.parse_short(),
-            Type::Int => self.parse_int(),
-            Type::Long => self.parse_long(),
-            Type::Float => self.parse_float(),
-            Type::Double => self.parse_double(),
-            Type::ByteArray => self.parse_byte_array(),
-            Type::Str => self.parse_str(),
-            Type::List => self.parse_list(),
-            Type::Compound => self.parse_compound(),
-            Type::IntArray => self.parse_int_array(),
-            Type::LongArray => self.parse_long_array(),
+            TagType::End => Err(ParseError::UnexpectedTag),
+            TagType::Byte => self.parse_byte(),
+            TagType::Short => self.parse_short(),
+            TagType::Int => self.parse_int(),
+            TagType::Long => self.parse_long(),
+            TagType::Float => self.parse_float(),
+            TagType::Double => self.parse_double(),
+            TagType::ByteArray => self.parse_byte_array(),
+            TagType::Str => self.parse_str(),
+            TagType::List => self.parse_list(),
+            TagType::Compound => self.parse_compound(),
+            TagType::IntArray => self.parse_int_array(),
+            TagType::LongArray => self.parse_long_array(),
         }
     }
 
-    /// parse subtypes
+    //// parse subtypes ////
 
     fn parse_byte(&mut self) -> Result<Value> {
         Ok(Value::Byte(self.read_byte()?))
@@ -193,29 +595,40 @@ impl<R: Read> Parser<R> {
     //// list ////
 
     fn parse_list(&mut self) -> Result<Value> {
+        if self.depth >= self.shallow_depth {
+            return self.skip_as_placeholder(TagType::List).map(Value::Compound);
+        }
+
+        self.enter()?;
+        let list = self.parse_list_payload();
+        self.leave();
+        list
+    }
+
+    fn parse_list_payload(&mut self) -> Result<Value> {
         if let Some(tag) = self.read_tag()? {
-            let size = self.read_int()? as usize;
+            let size = self.read_len(TagType::List)?;
 
             match tag {
-                Type::End => Ok(Value::EndList),
-                Type::Byte => {
+                TagType::End => Ok(Value::EndList),
+                TagType::Byte => {
                     if size == 0 {
                         Ok(Value::EmptyByteList)
                     } else {
                         self.parse_byte_list(size)
                     }
                 }
-                Type::Short => self.parse_short_list(size),
-                Type::Int => self.parse_int_list(size),
-                Type::Long => self.parse_long_list(size),
-                Type::Float => self.parse_float_list(size),
-                Type::Double => self.parse_double_list(size),
-                Type::ByteArray => self.parse_byte_array_list(size),
-                Type::Str => self.parse_str_list(size),
-                Type::List => self.parse_list_list(size),
-                Type::Compound => self.parse_compound_list(size),
-                Type::IntArray => self.parse_int_array_list(size),
-                Type::LongArray => self.parse_long_array_list(size),
+                TagType::Short => self.parse_short_list(size),
+                TagType::Int => self.parse_int_list(size),
+                TagType::Long => self.parse_long_list(size),
+                TagType::Float => self.parse_float_list(size),
+                TagType::Double => self.parse_double_list(size),
+                TagType::ByteArray => self.parse_byte_array_list(size),
+                TagType::Str => self.parse_str_list(size),
+                TagType::List => self.parse_list_list(size),
+                TagType::Compound => self.parse_compound_list(size),
+                TagType::IntArray => self.parse_int_array_list(size),
+                TagType::LongArray => self.parse_long_array_list(size),
             }
         } else {
             Err(ParseError::UnexpectedEndOfInput)
@@ -223,54 +636,31 @@ impl<R: Read> Parser<R> {
     }
 
     fn parse_byte_list(&mut self, size: usize) -> Result<Value> {
-        let mut list: Vec<i8> = Vec::with_capacity(size);
-        for _ in 0..size {
-            list.push(self.read_byte()?);
-        }
-        Ok(Value::ByteList(list))
+        Ok(Value::ByteList(self.read_fixed(size, i8::from_be_bytes, i8::from_le_bytes)?))
     }
 
     fn parse_short_list(&mut self, size: usize) -> Result<Value> {
-        let mut list: Vec<i16> = Vec::with_capacity(size);
-        for _ in 0..size {
-            list.push(self.read_short()?);
-        }
-        Ok(Value::ShortList(list))
+        Ok(Value::ShortList(self.read_fixed(size, i16::from_be_bytes, i16::from_le_bytes)?))
     }
 
     fn parse_int_list(&mut self, size: usize) -> Result<Value> {
-        let mut list: Vec<i32> = Vec::with_capacity(size);
-        for _ in 0..size {
-            list.push(self.read_int()?);
-        }
-        Ok(Value::IntList(list))
+        Ok(Value::IntList(self.read_ints(size)?))
     }
 
     fn parse_long_list(&mut self, size: usize) -> Result<Value> {
-        let mut list: Vec<i64> = Vec::with_capacity(size);
-        for _ in 0..size {
-            list.push(self.read_long()?);
-        }
-        Ok(Value::LongList(list))
+        Ok(Value::LongList(self.read_longs(size)?))
     }
 
     fn parse_float_list(&mut self, size: usize) -> Result<Value> {
-        let mut list: Vec<f32> = Vec::with_capacity(size);
-        for _ in 0..size {
-            list.push(self.read_float()?);
-        }
-        Ok(Value::FloatList(list))
+        Ok(Value::FloatList(self.read_fixed(size, |b| f32::from_bits(u32::from_be_bytes(b)), |b| f32::from_bits(u32::from_le_bytes(b)))?))
     }
 
     fn parse_double_list(&mut self, size: usize) -> Result<Value> {
-        let mut list: Vec<f64> = Vec::with_capacity(size);
-        for _ in 0..size {
-            list.push(self.read_double()?);
-        }
-        Ok(Value::DoubleList(list))
+        Ok(Value::DoubleList(self.read_fixed(size, |b| f64::from_bits(u64::from_be_bytes(b)), |b| f64::from_bits(u64::from_le_bytes(b)))?))
     }
 
     fn parse_byte_array_list(&mut self, size: usize) -> Result<Value> {
+        self.charge::<Vec<i8>>(size)?;
         let mut list: Vec<Vec<i8>> = Vec::with_capacity(size);
         for _ in 0..size {
             list.push(self.read_byte_array()?);
@@ -279,6 +669,7 @@ impl<R: Read> Parser<R> {
     }
 
     fn parse_str_list(&mut self, size: usize) -> Result<Value> {
+        self.charge::<String>(size)?;
         let mut list: Vec<String> = Vec::with_capacity(size);
         for _ in 0..size {
             list.push(self.read_str()?);
@@ -287,6 +678,7 @@ impl<R: Read> Parser<R> {
     }
 
     fn parse_list_list(&mut self, size: usize) -> Result<Value> {
+        self.charge::<Value>(size)?;
         let mut list: Vec<Value> = Vec::with_capacity(size);
         for _ in 0..size {
             list.push(self.parse_list()?);
@@ -295,6 +687,7 @@ impl<R: Read> Parser<R> {
     }
 
     fn parse_compound_list(&mut self, size: usize) -> Result<Value> {
+        self.charge::<Compound>(size)?;
         let mut list: Vec<Compound> = Vec::with_capacity(size);
         for _ in 0..size {
             list.push(self.read_compound()?);
@@ -303,6 +696,7 @@ impl<R: Read> Parser<R> {
     }
 
     fn parse_int_array_list(&mut self, size: usize) -> Result<Value> {
+        self.charge::<Vec<i32>>(size)?;
         let mut list: Vec<Vec<i32>> = Vec::with_capacity(size);
         for _ in 0..size {
             list.push(self.read_int_array()?);
@@ -311,6 +705,7 @@ impl<R: Read> Parser<R> {
     }
 
     fn parse_long_array_list(&mut self, size: usize) -> Result<Value> {
+        self.charge::<Vec<i64>>(size)?;
         let mut list: Vec<Vec<i64>> = Vec::with_capacity(size);
         for _ in 0..size {
             list.push(self.read_long_array()?);
@@ -318,13 +713,264 @@ impl<R: Read> Parser<R> {
         Ok(Value::LongArrayList(list))
     }
 
+    fn find_entry(&mut self, name: &str) -> Result<Option<Value>> {
+        loop {
+            match self.read_tag()? {
+                Some(TagType::End) => return Ok(None),
+                Some(tag) => {
+                    if self.read_str()? == name {
+                        return self.parse_value_with_tag(tag).map(Some);
+                    }
+                    self.skip_with_tag(tag)?;
+                }
+                None => return Err(ParseError::UnexpectedEndOfInput),
+            }
+        }
+    }
+
+    //// skip ////
+
+    pub(crate) fn skip_with_tag(&mut self, tag: TagType) -> Result<()> {
+        match tag {
+            TagType::End => Err(ParseError::UnexpectedTag),
+            TagType::Byte => self.skip_bytes(1),
+            TagType::Short => self.skip_bytes(2),
+            TagType::Int => self.skip_int(),
+            TagType::Long => self.skip_long(),
+            TagType::Float => self.skip_bytes(4),
+            TagType::Double => self.skip_bytes(8),
+            TagType::ByteArray => {
+                let size = self.read_len(TagType::ByteArray)?;
+                self.skip_bytes(size as u64)
+            }
+            TagType::Str => self.skip_str(),
+            TagType::List | TagType::Compound => self.skip_container(tag).map(|_| ()),
+            TagType::IntArray => {
+                let size = self.read_len(TagType::IntArray)?;
+                self.skip_ints(size)
+            }
+            TagType::LongArray => {
+                let size = self.read_len(TagType::LongArray)?;
+                self.skip_longs(size)
+            }
+        }
+    }
+
+    /// Skip list or compound, returning its element count.
+    fn skip_container(&mut self, tag: TagType) -> Result<usize> {
+        self.enter()?;
+        let result = match tag {
+            TagType::List => self.skip_list(),
+            _ => self.skip_compound(),
+        };
+        self.leave();
+        result
+    }
+
+    fn skip_as_placeholder(&mut self, tag: TagType) -> Result<Compound> {
+        let len = self.skip_container(tag)?;
+        let mut placeholder = Compound::new();
+        placeholder.insert(SHALLOW_TAG_KEY.to_string(), Value::Byte(tag.id() as i8));
+        placeholder.insert(SHALLOW_LEN_KEY.to_string(), Value::Int(len as i32));
+        Ok(placeholder)
+    }
+
+    fn skip_list(&mut self) -> Result<usize> {
+        let tag = self.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)?;
+        let size = self.read_len(TagType::List)?;
+
+        match tag {
+            TagType::End => Ok(()),
+            TagType::Byte => self.skip_bytes(size as u64),
+            TagType::Short => self.skip_bytes(size as u64 * 2),
+            TagType::Int => self.skip_ints(size),
+            TagType::Long => self.skip_longs(size),
+            TagType::Float => self.skip_bytes(size as u64 * 4),
+            TagType::Double => self.skip_bytes(size as u64 * 8),
+            _ => (0..size).try_for_each(|_| self.skip_with_tag(tag)),
+        }?;
+        Ok(size)
+    }
+
+    fn skip_compound(&mut self) -> Result<usize> {
+        let mut count = 0;
+        loop {
+            match self.read_tag()? {
+                Some(TagType::End) => return Ok(count),
+                Some(tag) => {
+                    self.skip_str()?;
+                    self.skip_with_tag(tag)?;
+                    count += 1;
+                }
+                None => return Err(ParseError::UnexpectedEndOfInput),
+            }
+        }
+    }
+
+    fn skip_int(&mut self) -> Result<()> {
+        match self.encoding {
+            Encoding::VarInt => self.read_int().map(|_| ()),
+            _ => self.skip_bytes(4),
+        }
+    }
+
+    fn skip_long(&mut self) -> Result<()> {
+        match self.encoding {
+            Encoding::VarInt => self.read_long().map(|_| ()),
+            _ => self.skip_bytes(8),
+        }
+    }
+
+    /// Skip `size` ints, at once unless they are VarInt.
+    fn skip_ints(&mut self, size: usize) -> Result<()> {
+        match self.encoding {
+            Encoding::VarInt => (0..size).try_for_each(|_| self.skip_int()),
+            _ => self.skip_bytes(size as u64 * 4),
+        }
+    }
+
+    /// Skip `size` longs, at once unless they are VarInt.
+    fn skip_longs(&mut self, size: usize) -> Result<()> {
+        match self.encoding {
+            Encoding::VarInt => (0..size).try_for_each(|_| self.skip_long()),
+            _ => self.skip_bytes(size as u64 * 8),
+        }
+    }
+
+    fn skip_str(&mut self) -> Result<()> {
+        let size = self.read_str_len()?;
+        self.skip_bytes(size as u64)
+    }
+
+    fn skip_bytes(&mut self, n: u64) -> Result<()> {
+        let skipped = io::copy(&mut (&mut self.r).take(n), &mut io::sink())?;
+        self.offset += skipped;
+        if skipped < n {
+            return Err(ParseError::UnexpectedEndOfInput);
+        }
+        Ok(())
+    }
+
+    //// visit ////
+
+    fn visit_value_with_tag<V: Visitor>(&mut self, tag: TagType, visitor: &mut V) -> Result<()> {
+        match tag {
+            TagType::Compound => {
+                self.enter()?;
+                let result = self.visit_compound(visitor);
+                self.leave();
+                result
+            }
+            TagType::List => {
+                self.enter()?;
+                let result = self.visit_list(visitor);
+                self.leave();
+                result
+            }
+            _ => {
+                let value = self.parse_value_with_tag(tag)?;
+                visitor.value(value);
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_compound<V: Visitor>(&mut self, visitor: &mut V) -> Result<()> {
+        visitor.begin_compound();
+
+        loop {
+            match self.read_tag()? {
+                Some(TagType::End) => break,
+                Some(tag) => {
+                    let name = self.read_str()?;
+                    visitor.key(&name);
+                    self.visit_value_with_tag(tag, visitor)?;
+                }
+                None => return Err(ParseError::UnexpectedEndOfInput),
+            }
+        }
+
+        visitor.end_compound();
+        Ok(())
+    }
+
+    fn visit_list<V: Visitor>(&mut self, visitor: &mut V) -> Result<()> {
+        let tag = self.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)?;
+        let size = self.read_len(TagType::List)?;
+
+        if tag == TagType::End {
+            visitor.begin_list(tag, 0);
+        } else {
+            visitor.begin_list(tag, size);
+            for _ in 0..size {
+                self.visit_value_with_tag(tag, visitor)?;
+            }
+        }
+
+        visitor.end_list();
+        Ok(())
+    }
+
+    //// depth ////
+
+    fn check_root(&self, tag: TagType) -> Result<()> {
+        match tag {
+            TagType::End => Err(ParseError::UnexpectedTag),
+            TagType::Compound => Ok(()),
+            _ if self.any_root => Ok(()),
+            _ => Err(ParseError::RootNotCompound(tag)),
+        }
+    }
+
+    fn check_trailing(&mut self) -> Result<()> {
+        if !self.strict {
+            return Ok(());
+        }
+
+        let rest = io::copy(&mut self.r, &mut io::sink())?;
+        if rest > 0 {
+            return Err(ParseError::TrailingData(rest));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError::DepthLimitExceeded(self.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub(crate) fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Count `size` elements of `T` against `max_total_bytes`.
+    fn charge<T>(&mut self, size: usize) -> Result<()> {
+        let bytes = size.saturating_mul(std::mem::size_of::<T>());
+        self.total_bytes = self.total_bytes.saturating_add(bytes);
+        if self.total_bytes > self.max_total_bytes {
+            return Err(ParseError::SizeLimitExceeded(self.max_total_bytes));
+        }
+        Ok(())
+    }
+
     //// read ////
 
-    fn read_tag(&mut self) -> Result<Option<Type>> {
+    pub(crate) fn read_tag(&mut self) -> Result<Option<TagType>> {
         let mut bs: [u8; 1] = [0; 1];
-
-        match self.r.read_exact(&mut bs) {
-            Ok(()) => Ok(Some(Type::try_from(bs[0])?)),
+        let offset = self.offset;
+
+        match self.read_exact(&mut bs) {
+            Ok(()) => match TagType::from_id(bs[0]) {
+                Some(tag) => Ok(Some(tag)),
+                None => Err(ParseError::Parse {
+                    offset,
+                    found: bs[0],
+                    context: "tag id",
+                }),
+            },
             Err(e) => {
                 if e.kind() == io::ErrorKind::UnexpectedEof {
                     Ok(None)
@@ -335,70 +981,168 @@ impl<R: Read> Parser<R> {
         }
     }
 
+    fn read_exact(&mut self, bs: &mut [u8]) -> io::Result<()> {
+        self.r.read_exact(bs)?;
+        self.offset += bs.len() as u64;
+        Ok(())
+    }
+
+    pub(crate) fn read_len(&mut self, tag: TagType) -> Result<usize> {
+        let offset = self.offset;
+        let size = self.read_int()?;
+
+        if size < 0 {
+            return Err(ParseError::Parse {
+                offset,
+                found: tag.id(),
+                context: "length of tag",
+            });
+        }
+
+        let size = size as usize;
+        if size > self.max_elements {
+            return Err(ParseError::LengthLimitExceeded {
+                declared: size,
+                limit: self.max_elements,
+            });
+        }
+
+        Ok(size)
+    }
+
+    /// Read unsigned VarInt of at most `bits` bits, failing if it is longer or has bits above them.
+    fn read_var(&mut self, bits: u32) -> Result<u64> {
+        let offset = self.offset;
+        let last = (bits as usize - 1) / 7;
+        let mut x = 0u64;
+
+        for i in 0..=last {
+            let b = self.read_byte()? as u8;
+            if i == last && u32::from(b) >> (bits - 7 * last as u32) != 0 {
+                return Err(ParseError::Parse {
+                    offset,
+                    found: b,
+                    context: "varint",
+                });
+            }
+            x |= u64::from(b & 0x7F) << (7 * i);
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+
+        Ok(x)
+    }
+
     fn read_byte(&mut self) -> Result<i8> {
         let mut bs = [0u8; 1];
-        self.r.read_exact(&mut bs)?;
+        self.read_exact(&mut bs)?;
         Ok(i8::from_be_bytes(bs))
     }
 
     fn read_short(&mut self) -> Result<i16> {
         let mut bs = [0u8; 2];
-        self.r.read_exact(&mut bs)?;
-        Ok(i16::from_be_bytes(bs))
+        self.read_exact(&mut bs)?;
+        match self.encoding {
+            Encoding::BigEndian => Ok(i16::from_be_bytes(bs)),
+            Encoding::LittleEndian | Encoding::VarInt => Ok(i16::from_le_bytes(bs)),
+        }
     }
 
     fn read_int(&mut self) -> Result<i32> {
+        if self.encoding == Encoding::VarInt {
+            let x = self.read_var(32)? as u32;
+            return Ok((x >> 1) as i32 ^ -((x & 1) as i32));
+        }
+
         let mut bs = [0u8; 4];
-        self.r.read_exact(&mut bs)?;
-        Ok(i32::from_be_bytes(bs))
+        self.read_exact(&mut bs)?;
+        match self.encoding {
+            Encoding::BigEndian => Ok(i32::from_be_bytes(bs)),
+            Encoding::LittleEndian | Encoding::VarInt => Ok(i32::from_le_bytes(bs)),
+        }
     }
 
     fn read_long(&mut self) -> Result<i64> {
+        if self.encoding == Encoding::VarInt {
+            let x = self.read_var(64)?;
+            return Ok((x >> 1) as i64 ^ -((x & 1) as i64));
+        }
+
         let mut bs = [0u8; 8];
-        self.r.read_exact(&mut bs)?;
-        Ok(i64::from_be_bytes(bs))
+        self.read_exact(&mut bs)?;
+        match self.encoding {
+            Encoding::BigEndian => Ok(i64::from_be_bytes(bs)),
+            Encoding::LittleEndian | Encoding::VarInt => Ok(i64::from_le_bytes(bs)),
+        }
     }
 
     fn read_float(&mut self) -> Result<f32> {
         let mut bs = [0u8; 4];
-        self.r.read_exact(&mut bs)?;
-        let x = u32::from_be_bytes(bs);
+        self.read_exact(&mut bs)?;
+        let x = match self.encoding {
+            Encoding::BigEndian => u32::from_be_bytes(bs),
+            Encoding::LittleEndian | Encoding::VarInt => u32::from_le_bytes(bs),
+        };
         Ok(f32::from_bits(x))
     }
 
     fn read_double(&mut self) -> Result<f64> {
         let mut bs = [0u8; 8];
-        self.r.read_exact(&mut bs)?;
-        let x = u64::from_be_bytes(bs);
+        self.read_exact(&mut bs)?;
+        let x = match self.encoding {
+            Encoding::BigEndian => u64::from_be_bytes(bs),
+            Encoding::LittleEndian | Encoding::VarInt => u64::from_le_bytes(bs),
+        };
         Ok(f64::from_bits(x))
     }
 
     fn read_byte_array(&mut self) -> Result<Vec<i8>> {
-        let size = self.read_int()? as usize;
-        let mut arr: Vec<i8> = Vec::with_capacity(size);
+        let size = self.read_len(TagType::ByteArray)?;
+        self.read_fixed(size, i8::from_be_bytes, i8::from_le_bytes)
+    }
 
-        for _ in 0..size {
-            arr.push(self.read_byte()?);
+    fn read_str_len(&mut self) -> Result<usize> {
+        match self.encoding {
+            Encoding::VarInt => Ok(self.read_var(32)? as usize),
+            _ => Ok(self.read_short()? as u16 as usize),
         }
-
-        Ok(arr)
     }
 
-    fn read_str(&mut self) -> Result<String> {
-        let size = self.read_short()? as usize;
+    pub(crate) fn read_str(&mut self) -> Result<String> {
+        let size = self.read_str_len()?;
+        self.charge::<u8>(size)?;
+
+        let offset = self.offset;
+        let mut bs = std::mem::take(&mut self.scratch);
+        bs.clear();
+        bs.resize(size, 0);
+
+        let s = match self.read_exact(&mut bs) {
+            Ok(()) => mutf8::decode(&bs).ok_or(ParseError::InvalidString { offset }),
+            Err(e) => Err(e.into()),
+        };
+        self.scratch = bs;
+        s
+    }
 
-        let mut bs = vec![0u8; size];
-        self.r.read_exact(bs.as_mut_slice())?;
+    fn read_compound(&mut self) -> Result<Compound> {
+        if self.depth >= self.shallow_depth {
+            return self.skip_as_placeholder(TagType::Compound);
+        }
 
-        Ok(String::from_utf8(bs)?)
+        self.enter()?;
+        let compound = self.read_compound_entries();
+        self.leave();
+        compound
     }
 
-    fn read_compound(&mut self) -> Result<Compound> {
+    fn read_compound_entries(&mut self) -> Result<Compound> {
         let mut root = Compound::new();
 
         loop {
             if let Some(tag) = self.read_tag()? {
-                if tag == Type::End {
+                if tag == TagType::End {
                     return Ok(root);
                 }
 
@@ -412,24 +1156,73 @@ impl<R: Read> Parser<R> {
     }
 
     fn read_int_array(&mut self) -> Result<Vec<i32>> {
-        let size = self.read_int()? as usize;
-        let mut arr: Vec<i32> = Vec::with_capacity(size);
-
-        for _ in 0..size {
-            arr.push(self.read_int()?);
-        }
-
-        Ok(arr)
+        let size = self.read_len(TagType::IntArray)?;
+        self.read_ints(size)
     }
 
     fn read_long_array(&mut self) -> Result<Vec<i64>> {
-        let size = self.read_int()? as usize;
-        let mut arr: Vec<i64> = Vec::with_capacity(size);
+        let size = self.read_len(TagType::LongArray)?;
+        self.read_longs(size)
+    }
 
-        for _ in 0..size {
-            arr.push(self.read_long()?);
+    fn read_ints(&mut self, size: usize) -> Result<Vec<i32>> {
+        if self.encoding == Encoding::VarInt {
+            self.charge::<i32>(size)?;
+            let mut xs = Vec::with_capacity(size);
+            for _ in 0..size {
+                xs.push(self.read_int()?);
+            }
+            return Ok(xs);
         }
+        self.read_fixed(size, i32::from_be_bytes, i32::from_le_bytes)
+    }
+
+    fn read_longs(&mut self, size: usize) -> Result<Vec<i64>> {
+        if self.encoding == Encoding::VarInt {
+            self.charge::<i64>(size)?;
+            let mut xs = Vec::with_capacity(size);
+            for _ in 0..size {
+                xs.push(self.read_long()?);
+            }
+            return Ok(xs);
+        }
+        self.read_fixed(size, i64::from_be_bytes, i64::from_le_bytes)
+    }
+
+    /// Read `size` fixed-width numbers, converting chunks of bytes at once.
+    ///
+    /// VarInt encoding uses little-endian here, so callers must handle varint int and long themselves.
+    fn read_fixed<T, const N: usize>(
+        &mut self,
+        size: usize,
+        be: fn([u8; N]) -> T,
+        le: fn([u8; N]) -> T,
+    ) -> Result<Vec<T>> {
+        const CHUNK_BYTES: usize = 8192;
+
+        let convert = match self.encoding {
+            Encoding::BigEndian => be,
+            Encoding::LittleEndian | Encoding::VarInt => le,
+        };
+
+        self.charge::<T>(size)?;
+        let mut xs = Vec::with_capacity(size);
+        let mut buf = std::mem::take(&mut self.scratch);
+        let mut left = size;
+        let result = loop {
+            if left == 0 {
+                break Ok(());
+            }
+            let n = left.min(CHUNK_BYTES / N);
+            buf.resize(n * N, 0);
+            if let Err(e) = self.read_exact(&mut buf) {
+                break Err(e.into());
+            }
+            xs.extend(buf.chunks_exact(N).map(|b| convert(b.try_into().unwrap())));
+            left -= n;
+        };
+        self.scratch = buf;
 
-        Ok(arr)
+        result.map(|()| xs)
     }
 }
@@ -0,0 +1,283 @@
+use core::convert::TryInto;
+use std::io;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::{ParseError, Result, SliceParser, DEFAULT_MAX_DEPTH};
+use crate::*;
+
+const CHUNK_BYTES: usize = 8192;
+
+/// NBT Parser over `tokio::io::AsyncRead`.
+///
+/// Each root tag is read into a buffer while following its structure, then decoded by `SliceParser`.
+/// Input should be plain big-endian NBT binary.
+#[derive(Debug)]
+pub struct AsyncParser<R> {
+    r: R,
+    offset: u64,
+    max_depth: usize,
+    max_elements: usize,
+    any_root: bool,
+    buf: Vec<u8>,
+}
+
+enum Frame {
+    Compound,
+    List(TagType, usize),
+}
+
+impl<R: AsyncRead + Unpin> AsyncParser<R> {
+    pub fn new(r: R) -> AsyncParser<R> {
+        AsyncParser {
+            r,
+            offset: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_elements: usize::MAX,
+            any_root: false,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Set nesting limit of compounds and lists.
+    pub fn max_depth(mut self, depth: usize) -> AsyncParser<R> {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Set element count limit of arrays and lists.
+    ///
+    /// Like `Parser::max_elements`, a declared length over the limit fails with `ParseError::LengthLimitExceeded`.
+    pub fn max_elements(mut self, max: usize) -> AsyncParser<R> {
+        self.max_elements = max;
+        self
+    }
+
+    /// Accept root tags of any type, like `Parser::allow_any_root`.
+    ///
+    /// Off by default: parsing fails with `ParseError::RootNotCompound` if a root tag is not a compound.
+    pub fn allow_any_root(mut self, allow: bool) -> AsyncParser<R> {
+        self.any_root = allow;
+        self
+    }
+
+    /// Parse all root tags until the input ends into a compound keyed by their names, like `Parser::parse`.
+    pub async fn parse(&mut self) -> Result<Value> {
+        let mut root = Compound::new();
+
+        while self.read_root().await? {
+            let (name, payload) = self.decode()?;
+            root.insert(name, payload);
+        }
+
+        Ok(Value::Compound(root))
+    }
+
+    /// Parse a single root tag and return it with its name.
+    ///
+    /// Bytes after the root tag are left unread, so messages on a stream can be parsed one by one.
+    pub async fn parse_named(&mut self) -> Result<(String, Value)> {
+        if !self.read_root().await? {
+            return Err(ParseError::UnexpectedEndOfInput);
+        }
+        self.decode()
+    }
+
+    /// Number of bytes read from the input so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Unwrap the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
+    fn decode(&mut self) -> Result<(String, Value)> {
+        let base = self.offset - self.buf.len() as u64;
+        let parsed = SliceParser::new(&self.buf)
+            .max_depth(self.max_depth)
+            .allow_any_root(self.any_root)
+            .parse_named();
+        parsed.map_err(|e| match e {
+            ParseError::InvalidString { offset } => ParseError::InvalidString { offset: base + offset },
+            ParseError::Parse { offset, found, context } => ParseError::Parse {
+                offset: base + offset,
+                found,
+                context,
+            },
+            e => e,
+        })
+    }
+
+    //// framing ////
+
+    /// Read a root tag into `buf`. Returns `false` if the input ended before it.
+    async fn read_root(&mut self) -> Result<bool> {
+        self.buf.clear();
+
+        let tag = match self.read_tag().await? {
+            Some(TagType::End) => return Err(ParseError::UnexpectedTag),
+            Some(TagType::Compound) => TagType::Compound,
+            Some(tag) if self.any_root => tag,
+            Some(tag) => return Err(ParseError::RootNotCompound(tag)),
+            None => return Ok(false),
+        };
+        self.read_str().await?;
+        self.read_payload(tag).await?;
+        Ok(true)
+    }
+
+    /// Read payload of `tag`, keeping nested compounds and lists on a stack instead of recursing.
+    async fn read_payload(&mut self, tag: TagType) -> Result<()> {
+        let mut stack = Vec::new();
+        let mut next = Some(tag);
+
+        loop {
+            if let Some(tag) = next.take() {
+                match tag {
+                    TagType::End => return Err(ParseError::UnexpectedTag),
+                    TagType::ByteArray | TagType::IntArray | TagType::LongArray => {
+                        let size = self.read_len(tag).await?;
+                        self.read_bytes(size.saturating_mul(element_width(tag))).await?;
+                    }
+                    TagType::Str => self.read_str().await?,
+                    TagType::List => {
+                        self.check_depth(stack.len())?;
+                        let element = self.read_tag().await?.ok_or(ParseError::UnexpectedEndOfInput)?;
+                        let size = self.read_len(TagType::List).await?;
+                        match (element, fixed_width(element)) {
+                            (TagType::End, _) => {}
+                            (_, Some(width)) => self.read_bytes(size.saturating_mul(width)).await?,
+                            _ => stack.push(Frame::List(element, size)),
+                        }
+                    }
+                    TagType::Compound => {
+                        self.check_depth(stack.len())?;
+                        stack.push(Frame::Compound);
+                    }
+                    _ => self.read_bytes(element_width(tag)).await?,
+                }
+            }
+
+            match stack.last_mut() {
+                None => return Ok(()),
+                Some(Frame::List(_, 0)) => {
+                    stack.pop();
+                }
+                Some(Frame::List(element, left)) => {
+                    *left -= 1;
+                    next = Some(*element);
+                }
+                Some(Frame::Compound) => match self.read_tag().await?.ok_or(ParseError::UnexpectedEndOfInput)? {
+                    TagType::End => {
+                        stack.pop();
+                    }
+                    tag => {
+                        self.read_str().await?;
+                        next = Some(tag);
+                    }
+                },
+            }
+        }
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<()> {
+        if depth >= self.max_depth {
+            return Err(ParseError::DepthLimitExceeded(self.max_depth));
+        }
+        Ok(())
+    }
+
+    //// read ////
+
+    async fn read_tag(&mut self) -> Result<Option<TagType>> {
+        let offset = self.offset;
+        let mut bs = [0u8; 1];
+
+        match self.r.read_exact(&mut bs).await {
+            Ok(_) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        self.offset += 1;
+        self.buf.push(bs[0]);
+
+        match TagType::from_id(bs[0]) {
+            Some(tag) => Ok(Some(tag)),
+            None => Err(ParseError::Parse {
+                offset,
+                found: bs[0],
+                context: "tag id",
+            }),
+        }
+    }
+
+    async fn read_str(&mut self) -> Result<()> {
+        self.read_bytes(2).await?;
+        let len = u16::from_be_bytes(self.last_bytes());
+        self.read_bytes(len as usize).await
+    }
+
+    async fn read_len(&mut self, tag: TagType) -> Result<usize> {
+        let offset = self.offset;
+        self.read_bytes(4).await?;
+        let size = i32::from_be_bytes(self.last_bytes());
+
+        if size < 0 {
+            return Err(ParseError::Parse {
+                offset,
+                found: tag.id(),
+                context: "length of tag",
+            });
+        }
+
+        let size = size as usize;
+        if size > self.max_elements {
+            return Err(ParseError::LengthLimitExceeded {
+                declared: size,
+                limit: self.max_elements,
+            });
+        }
+
+        Ok(size)
+    }
+
+    /// Append `n` bytes to `buf`, growing it chunk by chunk so that a bogus length fails at the end of input.
+    async fn read_bytes(&mut self, n: usize) -> Result<()> {
+        let mut left = n;
+        while left > 0 {
+            let start = self.buf.len();
+            let k = left.min(CHUNK_BYTES);
+            self.buf.resize(start + k, 0);
+            self.r.read_exact(&mut self.buf[start..]).await?;
+            self.offset += k as u64;
+            left -= k;
+        }
+        Ok(())
+    }
+
+    fn last_bytes<const N: usize>(&self) -> [u8; N] {
+        self.buf[self.buf.len() - N..].try_into().unwrap()
+    }
+}
+
+/// Payload size of numbers, and element size of arrays.
+fn element_width(tag: TagType) -> usize {
+    match tag {
+        TagType::ByteArray => 1,
+        TagType::IntArray => 4,
+        TagType::LongArray => 8,
+        _ => fixed_width(tag).unwrap_or(0),
+    }
+}
+
+fn fixed_width(tag: TagType) -> Option<usize> {
+    match tag {
+        TagType::Byte => Some(1),
+        TagType::Short => Some(2),
+        TagType::Int | TagType::Float => Some(4),
+        TagType::Long | TagType::Double => Some(8),
+        _ => None,
+    }
+}
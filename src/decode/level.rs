@@ -0,0 +1,85 @@
+use std::io::Read;
+
+use super::{ParseError, Parser, Result};
+use crate::*;
+
+/// Common fields of the `Data` compound in `level.dat`, read by `read_level_summary`.
+///
+/// A field is `None` if it is missing or has an unexpected type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelSummary {
+    /// `LevelName`.
+    pub level_name: Option<String>,
+    /// `Version.Name`, like `1.13.2`. Written since 1.9.
+    pub version_name: Option<String>,
+    /// `DataVersion`.
+    pub data_version: Option<i32>,
+    /// `LastPlayed`, in milliseconds since the Unix epoch.
+    pub last_played: Option<i64>,
+    /// `GameType`: 0 survival, 1 creative, 2 adventure, 3 spectator.
+    pub game_type: Option<i32>,
+    /// `hardcore`.
+    pub hardcore: Option<bool>,
+}
+
+/// Read common fields of `level.dat` without decoding the rest of it.
+///
+/// Input should be plain NBT binary, so wrap the file in a gzip decoder.
+/// Other entries, like `Player`, are skipped, and the input after the `Data` compound is left unread.
+pub fn read_level_summary<R: Read>(r: R) -> Result<LevelSummary> {
+    let mut p = Parser::new(r);
+    let mut summary = LevelSummary::default();
+
+    match p.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)? {
+        TagType::End => return Err(ParseError::UnexpectedTag),
+        TagType::Compound => {}
+        tag => return Err(ParseError::RootNotCompound(tag)),
+    }
+    p.read_str()?;
+
+    p.enter()?;
+    while let Some((tag, name)) = next_entry(&mut p)? {
+        if name == "Data" && tag == TagType::Compound {
+            p.enter()?;
+            read_data(&mut p, &mut summary)?;
+            break;
+        }
+        p.skip_with_tag(tag)?;
+    }
+
+    Ok(summary)
+}
+
+fn read_data<R: Read>(p: &mut Parser<R>, summary: &mut LevelSummary) -> Result<()> {
+    while let Some((tag, name)) = next_entry(p)? {
+        match name.as_str() {
+            "LevelName" | "Version" | "DataVersion" | "LastPlayed" | "GameType" | "hardcore" => {}
+            _ => {
+                p.skip_with_tag(tag)?;
+                continue;
+            }
+        }
+
+        let value = p.parse_value_with_tag(tag)?;
+        match name.as_str() {
+            "LevelName" => summary.level_name = value.str().ok().map(String::from),
+            "Version" => summary.version_name = value.get("Name").and_then(Value::str).ok().map(String::from),
+            "DataVersion" => summary.data_version = value.int().ok(),
+            "LastPlayed" => summary.last_played = value.long().ok(),
+            "GameType" => summary.game_type = value.int().ok(),
+            _ => summary.hardcore = value.byte().ok().map(|x| x != 0),
+        }
+    }
+    Ok(())
+}
+
+/// Read the tag and name of the next compound entry, or `None` at the end of the compound.
+fn next_entry<R: Read>(p: &mut Parser<R>) -> Result<Option<(TagType, String)>> {
+    match p.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)? {
+        TagType::End => {
+            p.leave();
+            Ok(None)
+        }
+        tag => Ok(Some((tag, p.read_str()?))),
+    }
+}
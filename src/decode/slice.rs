@@ -0,0 +1,316 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use super::{ParseError, Result, DEFAULT_MAX_DEPTH};
+use crate::*;
+
+/// NBT Parser over in-memory bytes.
+///
+/// Unlike `Parser`, this does not need `std::io::Read`, so it is available without the `std` feature.
+/// Input should be plain big-endian NBT binary.
+/// Declared lengths longer than the rest of the input fail before allocating.
+#[derive(Debug, Clone)]
+pub struct SliceParser<'a> {
+    bs: &'a [u8],
+    pos: usize,
+    depth: usize,
+    max_depth: usize,
+    any_root: bool,
+}
+
+impl<'a> SliceParser<'a> {
+    pub fn new(bs: &'a [u8]) -> SliceParser<'a> {
+        SliceParser {
+            bs,
+            pos: 0,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            any_root: false,
+        }
+    }
+
+    /// Set nesting limit of compounds and lists.
+    pub fn max_depth(mut self, depth: usize) -> SliceParser<'a> {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Accept root tags of any type, like `Parser::allow_any_root`.
+    ///
+    /// Off by default: parsing fails with `ParseError::RootNotCompound` if a root tag is not a compound.
+    pub fn allow_any_root(mut self, allow: bool) -> SliceParser<'a> {
+        self.any_root = allow;
+        self
+    }
+
+    /// Parse all root tags into a compound keyed by their names, like `Parser::parse`.
+    pub fn parse(&mut self) -> Result<Value> {
+        Ok(ValueRef::Compound(self.parse_ref()?).into_owned())
+    }
+
+    /// Parse a single root tag and return it with its name, like `Parser::parse_named`.
+    pub fn parse_named(&mut self) -> Result<(String, Value)> {
+        let (name, value) = self.parse_named_ref()?;
+        Ok((name.into_owned(), value.into_owned()))
+    }
+
+    /// Parse all root tags like `parse`, borrowing strings and byte arrays from the input.
+    pub fn parse_ref(&mut self) -> Result<CompoundRef<'a>> {
+        let mut root = CompoundRef::new();
+
+        while let Some(tag) = self.read_tag()? {
+            self.check_root(tag)?;
+            let name = self.read_str()?;
+            let payload = self.parse_value_with_tag(tag)?;
+            root.insert(name, payload);
+        }
+
+        Ok(root)
+    }
+
+    /// Parse a single root tag like `parse_named`, borrowing strings and byte arrays from the input.
+    pub fn parse_named_ref(&mut self) -> Result<(Cow<'a, str>, ValueRef<'a>)> {
+        match self.read_tag()? {
+            Some(tag) => {
+                self.check_root(tag)?;
+                let name = self.read_str()?;
+                let payload = self.parse_value_with_tag(tag)?;
+                Ok((name, payload))
+            }
+            None => Err(ParseError::UnexpectedEndOfInput),
+        }
+    }
+
+    /// Get number of bytes consumed so far.
+    pub fn offset(&self) -> u64 {
+        self.pos as u64
+    }
+
+    /// Get the unread rest of the input.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bs[self.pos..]
+    }
+
+    //// value ////
+
+    fn parse_value_with_tag(&mut self, tag: TagType) -> Result<ValueRef<'a>> {
+        match tag {
+            TagType::End => Err(ParseError::UnexpectedTag),
+            TagType::Byte => Ok(ValueRef::Byte(self.read_byte()?)),
+            TagType::Short => Ok(ValueRef::Short(self.read_short()?)),
+            TagType::Int => Ok(ValueRef::Int(self.read_int()?)),
+            TagType::Long => Ok(ValueRef::Long(self.read_long()?)),
+            TagType::Float => Ok(ValueRef::Float(self.read_float()?)),
+            TagType::Double => Ok(ValueRef::Double(self.read_double()?)),
+            TagType::ByteArray => Ok(ValueRef::ByteArray(self.read_byte_array()?)),
+            TagType::Str => Ok(ValueRef::Str(self.read_str()?)),
+            TagType::List => self.parse_list(),
+            TagType::Compound => Ok(ValueRef::Compound(self.read_compound()?)),
+            TagType::IntArray => Ok(ValueRef::IntArray(self.read_int_array()?)),
+            TagType::LongArray => Ok(ValueRef::LongArray(self.read_long_array()?)),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<ValueRef<'a>> {
+        self.enter()?;
+        let list = self.parse_list_payload();
+        self.leave();
+        list
+    }
+
+    fn parse_list_payload(&mut self) -> Result<ValueRef<'a>> {
+        let tag = self.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)?;
+        let size = self.read_len(TagType::List, min_width(tag))?;
+
+        let list = match tag {
+            TagType::End => ValueRef::EndList,
+            TagType::Byte if size == 0 => ValueRef::EmptyByteList,
+            TagType::Byte => ValueRef::ByteList(self.read_bytes(size)?),
+            TagType::Short => ValueRef::ShortList(self.read_vec(size, i16::from_be_bytes)?),
+            TagType::Int => ValueRef::IntList(self.read_vec(size, i32::from_be_bytes)?),
+            TagType::Long => ValueRef::LongList(self.read_vec(size, i64::from_be_bytes)?),
+            TagType::Float => ValueRef::FloatList(self.read_vec(size, |b| f32::from_bits(u32::from_be_bytes(b)))?),
+            TagType::Double => ValueRef::DoubleList(self.read_vec(size, |b| f64::from_bits(u64::from_be_bytes(b)))?),
+            TagType::ByteArray => ValueRef::ByteArrayList(self.repeat(size, SliceParser::read_byte_array)?),
+            TagType::Str => ValueRef::StrList(self.repeat(size, SliceParser::read_str)?),
+            TagType::List => ValueRef::ListList(self.repeat(size, SliceParser::parse_list)?),
+            TagType::Compound => ValueRef::CompoundList(self.repeat(size, SliceParser::read_compound)?),
+            TagType::IntArray => ValueRef::IntArrayList(self.repeat(size, SliceParser::read_int_array)?),
+            TagType::LongArray => ValueRef::LongArrayList(self.repeat(size, SliceParser::read_long_array)?),
+        };
+        Ok(list)
+    }
+
+    fn read_compound(&mut self) -> Result<CompoundRef<'a>> {
+        self.enter()?;
+        let compound = self.read_compound_entries();
+        self.leave();
+        compound
+    }
+
+    fn read_compound_entries(&mut self) -> Result<CompoundRef<'a>> {
+        let mut compound = CompoundRef::new();
+
+        loop {
+            match self.read_tag()? {
+                Some(TagType::End) => return Ok(compound),
+                Some(tag) => {
+                    let name = self.read_str()?;
+                    let payload = self.parse_value_with_tag(tag)?;
+                    compound.insert(name, payload);
+                }
+                None => return Err(ParseError::UnexpectedEndOfInput),
+            }
+        }
+    }
+
+    fn repeat<T>(&mut self, size: usize, mut f: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut xs = Vec::with_capacity(size);
+        for _ in 0..size {
+            xs.push(f(self)?);
+        }
+        Ok(xs)
+    }
+
+    fn check_root(&self, tag: TagType) -> Result<()> {
+        match tag {
+            TagType::End => Err(ParseError::UnexpectedTag),
+            TagType::Compound => Ok(()),
+            _ if self.any_root => Ok(()),
+            _ => Err(ParseError::RootNotCompound(tag)),
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.max_depth {
+            return Err(ParseError::DepthLimitExceeded(self.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    //// read ////
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.bs.len() - self.pos < n {
+            return Err(ParseError::UnexpectedEndOfInput);
+        }
+        let bs = &self.bs[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bs)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn read_tag(&mut self) -> Result<Option<TagType>> {
+        let offset = self.pos as u64;
+        match self.bs.get(self.pos) {
+            Some(&id) => {
+                self.pos += 1;
+                TagType::from_id(id).map(Some).ok_or(ParseError::Parse {
+                    offset,
+                    found: id,
+                    context: "tag id",
+                })
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Read length of array or list with elements at least `width` bytes each.
+    fn read_len(&mut self, tag: TagType, width: usize) -> Result<usize> {
+        let offset = self.pos as u64;
+        let size = self.read_int()?;
+
+        if size < 0 {
+            return Err(ParseError::Parse {
+                offset,
+                found: tag.id(),
+                context: "length of tag",
+            });
+        }
+
+        let size = size as usize;
+        if size.saturating_mul(width) > self.remaining().len() {
+            return Err(ParseError::UnexpectedEndOfInput);
+        }
+        Ok(size)
+    }
+
+    fn read_vec<T, const N: usize>(&mut self, size: usize, f: impl Fn([u8; N]) -> T) -> Result<Vec<T>> {
+        let bs = self.take(size * N)?;
+        Ok(bs.chunks_exact(N).map(|b| f(b.try_into().unwrap())).collect())
+    }
+
+    fn read_byte(&mut self) -> Result<i8> {
+        Ok(self.take_array::<1>()?[0] as i8)
+    }
+
+    fn read_short(&mut self) -> Result<i16> {
+        Ok(i16::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_int(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_long(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_float(&mut self) -> Result<f32> {
+        Ok(f32::from_bits(u32::from_be_bytes(self.take_array()?)))
+    }
+
+    fn read_double(&mut self) -> Result<f64> {
+        Ok(f64::from_bits(u64::from_be_bytes(self.take_array()?)))
+    }
+
+    fn read_bytes(&mut self, size: usize) -> Result<&'a [i8]> {
+        let bs = self.take(size)?;
+        // SAFETY: i8 and u8 have the same size and alignment, and every bit pattern is valid for both.
+        Ok(unsafe { core::slice::from_raw_parts(bs.as_ptr() as *const i8, bs.len()) })
+    }
+
+    fn read_byte_array(&mut self) -> Result<&'a [i8]> {
+        let size = self.read_len(TagType::ByteArray, 1)?;
+        self.read_bytes(size)
+    }
+
+    fn read_int_array(&mut self) -> Result<Vec<i32>> {
+        let size = self.read_len(TagType::IntArray, 4)?;
+        self.read_vec(size, i32::from_be_bytes)
+    }
+
+    fn read_long_array(&mut self) -> Result<Vec<i64>> {
+        let size = self.read_len(TagType::LongArray, 8)?;
+        self.read_vec(size, i64::from_be_bytes)
+    }
+
+    fn read_str(&mut self) -> Result<Cow<'a, str>> {
+        let size = self.read_short()? as u16 as usize;
+        let offset = self.pos as u64;
+        let bs = self.take(size)?;
+        mutf8::decode_cow(bs).ok_or(ParseError::InvalidString { offset })
+    }
+}
+
+/// Smallest encoded size of a list element.
+fn min_width(tag: TagType) -> usize {
+    match tag {
+        TagType::End => 0,
+        TagType::Byte | TagType::Compound => 1,
+        TagType::Short | TagType::Str => 2,
+        TagType::Int | TagType::Float | TagType::ByteArray | TagType::IntArray | TagType::LongArray => 4,
+        TagType::Long | TagType::Double => 8,
+        TagType::List => 5,
+    }
+}
@@ -0,0 +1,121 @@
+//! Structural difference of two values.
+//!
+//! ```
+//! use raw_nbt::decode::from_snbt;
+//! use raw_nbt::diff::{diff, ChangeKind};
+//!
+//! let old = from_snbt("{Data:{GameType:0,Time:10L}}").unwrap();
+//! let new = from_snbt("{Data:{GameType:1,Difficulty:2b}}").unwrap();
+//!
+//! let changes = diff(&old, &new);
+//! let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+//! assert_eq!(paths, ["Data.Difficulty", "Data.GameType", "Data.Time"]);
+//! assert!(matches!(changes[2].kind, ChangeKind::Removed(_)));
+//! ```
+
+use crate::*;
+
+/// A difference found by `diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change<'a> {
+    /// Path of the entry in the syntax of `Value::path`. Empty for the root.
+    pub path: String,
+    pub kind: ChangeKind<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind<'a> {
+    /// Entry exists only in the new value.
+    Added(&'a Value),
+    /// Entry exists only in the old value.
+    Removed(&'a Value),
+    /// Entry differs, as `(old, new)`.
+    Modified(&'a Value, &'a Value),
+}
+
+/// List changes from `a` to `b`, sorted by path within each compound.
+///
+/// Compounds are compared by key. Lists of lists and lists of compounds of the same length are compared
+/// element by element, with paths like `Items[0]`; other differing lists are reported as a whole.
+/// All empty list representations are equal, like `Value::semantic_eq`.
+pub fn diff<'a>(a: &'a Value, b: &'a Value) -> Vec<Change<'a>> {
+    let mut changes = Vec::new();
+    diff_in(a, b, &mut String::new(), &mut changes);
+    changes
+}
+
+fn diff_in<'a>(a: &'a Value, b: &'a Value, path: &mut String, changes: &mut Vec<Change<'a>>) {
+    match (a, b) {
+        (Value::Compound(x), Value::Compound(y)) => diff_compound(x, y, path, changes),
+        (Value::ListList(xs), Value::ListList(ys)) if xs.len() == ys.len() => {
+            for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{}]", i));
+                diff_in(x, y, path, changes);
+                path.truncate(len);
+            }
+        }
+        (Value::CompoundList(xs), Value::CompoundList(ys)) if xs.len() == ys.len() => {
+            for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{}]", i));
+                diff_compound(x, y, path, changes);
+                path.truncate(len);
+            }
+        }
+        _ => {
+            if !a.semantic_eq(b) {
+                changes.push(Change {
+                    path: path.clone(),
+                    kind: ChangeKind::Modified(a, b),
+                });
+            }
+        }
+    }
+}
+
+fn diff_compound<'a>(a: &'a Compound, b: &'a Compound, path: &mut String, changes: &mut Vec<Change<'a>>) {
+    let mut xs = a.iter().peekable();
+    let mut ys = b.iter().peekable();
+
+    loop {
+        let len = path.len();
+        let (name, kind) = match (xs.peek(), ys.peek()) {
+            (None, None) => break,
+            (Some((kx, _)), Some((ky, _))) if kx == ky => {
+                let (name, x) = xs.next().unwrap();
+                let (_, y) = ys.next().unwrap();
+                push_name(path, name);
+                diff_in(x, y, path, changes);
+                path.truncate(len);
+                continue;
+            }
+            (Some((kx, _)), Some((ky, _))) if kx > ky => {
+                let (name, y) = ys.next().unwrap();
+                (name, ChangeKind::Added(y))
+            }
+            (Some(_), _) => {
+                let (name, x) = xs.next().unwrap();
+                (name, ChangeKind::Removed(x))
+            }
+            (None, Some(_)) => {
+                let (name, y) = ys.next().unwrap();
+                (name, ChangeKind::Added(y))
+            }
+        };
+
+        push_name(path, name);
+        changes.push(Change {
+            path: path.clone(),
+            kind,
+        });
+        path.truncate(len);
+    }
+}
+
+fn push_name(path: &mut String, name: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(name);
+}
@@ -0,0 +1,263 @@
+use std::convert::From;
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+use crate::*;
+
+/// Write `value` as a named root tag of plain NBT binary.
+///
+/// Output is buffered, so `w` need not be.
+pub fn to_writer<W: Write>(w: W, name: &str, value: &Value) -> Result<()> {
+    let mut enc = Encoder::new(io::BufWriter::new(w));
+    enc.write(name, value)?;
+    enc.w.flush()?;
+    Ok(())
+}
+
+/// Write `value` as a named root tag of gzip compressed NBT binary, like vanilla `level.dat`.
+#[cfg(feature = "compression")]
+pub fn to_gzip_writer<W: Write>(w: W, name: &str, value: &Value) -> Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let mut enc = Encoder::new(io::BufWriter::new(GzEncoder::new(w, Compression::default())));
+    enc.write(name, value)?;
+    let gz = enc.into_inner().into_inner().map_err(io::IntoInnerError::into_error)?;
+    gz.finish()?.flush()?;
+    Ok(())
+}
+
+/// NBT Encoder.
+///
+/// Output stream is plain NBT binary.
+/// If you need compress, use other crate (ex: flate2).
+///
+/// Each number, length, and name is written by its own `write_all` call,
+/// so wrap a file or socket in `std::io::BufWriter` and flush it after writing.
+#[derive(Debug)]
+pub struct Encoder<W> {
+    w: W,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(w: W) -> Encoder<W> {
+        Encoder { w }
+    }
+
+    /// Write a named tag.
+    ///
+    /// To write a whole file parsed by `decode::parse`, write each entry of the returned compound.
+    pub fn write(&mut self, name: &str, value: &Value) -> Result<()> {
+        self.write_tag(value.tag_type())?;
+        self.write_str(name)?;
+        self.write_value(value)
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    //// value ////
+
+    pub(crate) fn write_value(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::Byte(x) => self.write_byte(*x),
+            Value::Short(x) => self.write_short(*x),
+            Value::Int(x) => self.write_int(*x),
+            Value::Long(x) => self.write_long(*x),
+            Value::Float(x) => self.write_float(*x),
+            Value::Double(x) => self.write_double(*x),
+            Value::ByteArray(xs) => self.write_byte_array(xs),
+            Value::Str(x) => self.write_str(x),
+            Value::Compound(x) => self.write_compound(x),
+            Value::IntArray(xs) => self.write_int_array(xs),
+            Value::LongArray(xs) => self.write_long_array(xs),
+            _ => self.write_list(value),
+        }
+    }
+
+    //// list ////
+
+    fn write_list(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::EndList => self.write_list_header(TagType::End, 0),
+            Value::EmptyByteList => self.write_list_header(TagType::Byte, 0),
+            Value::ByteList(xs) => {
+                self.write_list_header(TagType::Byte, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_byte(*x))
+            }
+            Value::ShortList(xs) => {
+                self.write_list_header(TagType::Short, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_short(*x))
+            }
+            Value::IntList(xs) => {
+                self.write_list_header(TagType::Int, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_int(*x))
+            }
+            Value::LongList(xs) => {
+                self.write_list_header(TagType::Long, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_long(*x))
+            }
+            Value::FloatList(xs) => {
+                self.write_list_header(TagType::Float, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_float(*x))
+            }
+            Value::DoubleList(xs) => {
+                self.write_list_header(TagType::Double, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_double(*x))
+            }
+            Value::ByteArrayList(xs) => {
+                self.write_list_header(TagType::ByteArray, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_byte_array(x))
+            }
+            Value::StrList(xs) => {
+                self.write_list_header(TagType::Str, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_str(x))
+            }
+            Value::ListList(xs) => {
+                self.write_list_header(TagType::List, xs.len())?;
+                for x in xs {
+                    if x.tag_type() != TagType::List {
+                        return Err(EncodeError::InvalidListElement(x.val_type()));
+                    }
+                    self.write_list(x)?;
+                }
+                Ok(())
+            }
+            Value::CompoundList(xs) => {
+                self.write_list_header(TagType::Compound, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_compound(x))
+            }
+            Value::IntArrayList(xs) => {
+                self.write_list_header(TagType::IntArray, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_int_array(x))
+            }
+            Value::LongArrayList(xs) => {
+                self.write_list_header(TagType::LongArray, xs.len())?;
+                xs.iter().try_for_each(|x| self.write_long_array(x))
+            }
+            _ => Err(EncodeError::InvalidListElement(value.val_type())),
+        }
+    }
+
+    fn write_list_header(&mut self, tag: TagType, size: usize) -> Result<()> {
+        self.write_tag(tag)?;
+        self.write_len(size)
+    }
+
+    //// write ////
+
+    pub(crate) fn write_tag(&mut self, tag: TagType) -> Result<()> {
+        self.w.write_all(&[tag.id()])?;
+        Ok(())
+    }
+
+    pub(crate) fn write_len(&mut self, size: usize) -> Result<()> {
+        if size > i32::MAX as usize {
+            return Err(EncodeError::LengthTooLarge(size));
+        }
+        self.write_int(size as i32)
+    }
+
+    fn write_byte(&mut self, x: i8) -> Result<()> {
+        self.w.write_all(&x.to_be_bytes())?;
+        Ok(())
+    }
+
+    fn write_short(&mut self, x: i16) -> Result<()> {
+        self.w.write_all(&x.to_be_bytes())?;
+        Ok(())
+    }
+
+    fn write_int(&mut self, x: i32) -> Result<()> {
+        self.w.write_all(&x.to_be_bytes())?;
+        Ok(())
+    }
+
+    fn write_long(&mut self, x: i64) -> Result<()> {
+        self.w.write_all(&x.to_be_bytes())?;
+        Ok(())
+    }
+
+    fn write_float(&mut self, x: f32) -> Result<()> {
+        self.w.write_all(&x.to_bits().to_be_bytes())?;
+        Ok(())
+    }
+
+    fn write_double(&mut self, x: f64) -> Result<()> {
+        self.w.write_all(&x.to_bits().to_be_bytes())?;
+        Ok(())
+    }
+
+    fn write_byte_array(&mut self, xs: &[i8]) -> Result<()> {
+        self.write_len(xs.len())?;
+        xs.iter().try_for_each(|x| self.write_byte(*x))
+    }
+
+    pub(crate) fn write_str(&mut self, x: &str) -> Result<()> {
+        let bs = mutf8::encode(x);
+        if bs.len() > u16::MAX as usize {
+            return Err(EncodeError::StringTooLong(bs.len()));
+        }
+
+        self.w.write_all(&(bs.len() as u16).to_be_bytes())?;
+        self.w.write_all(&bs)?;
+        Ok(())
+    }
+
+    fn write_compound(&mut self, x: &Compound) -> Result<()> {
+        for (name, value) in x {
+            self.write(name, value)?;
+        }
+        self.write_tag(TagType::End)
+    }
+
+    fn write_int_array(&mut self, xs: &[i32]) -> Result<()> {
+        self.write_len(xs.len())?;
+        xs.iter().try_for_each(|x| self.write_int(*x))
+    }
+
+    fn write_long_array(&mut self, xs: &[i64]) -> Result<()> {
+        self.write_len(xs.len())?;
+        xs.iter().try_for_each(|x| self.write_long(*x))
+    }
+}
+
+/// NBT Encode error.
+#[derive(Debug)]
+pub enum EncodeError {
+    InvalidListElement(&'static str),
+    LengthTooLarge(usize),
+    StringTooLong(usize),
+    WriteError(io::Error),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::InvalidListElement(actual) => write!(f, "list list contains non-list element '{}'", actual),
+            EncodeError::LengthTooLarge(size) => write!(f, "too many elements: {}", size),
+            EncodeError::StringTooLong(size) => write!(f, "too long string: {} bytes", size),
+            EncodeError::WriteError(ref cause) => cause.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EncodeError::WriteError(ref cause) => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EncodeError {
+    fn from(e: io::Error) -> EncodeError {
+        EncodeError::WriteError(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, EncodeError>;
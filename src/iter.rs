@@ -0,0 +1,154 @@
+use alloc::collections::btree_map;
+use alloc::vec::Vec;
+use core::iter;
+use core::slice;
+
+use crate::*;
+
+/// Depth-first iterator over a value and all values nested in it.
+///
+/// Created by `Value::iter_recursive`.
+#[derive(Debug, Clone)]
+pub struct Recursive<'a> {
+    root: Option<&'a Value>,
+    stack: Vec<Frame<'a>>,
+}
+
+#[derive(Debug, Clone)]
+enum Frame<'a> {
+    Entries(btree_map::Values<'a, String, Value>),
+    List(slice::Iter<'a, Value>),
+    Compounds(slice::Iter<'a, Compound>),
+}
+
+impl<'a> Recursive<'a> {
+    pub(crate) fn new(root: &'a Value) -> Recursive<'a> {
+        Recursive {
+            root: Some(root),
+            stack: Vec::new(),
+        }
+    }
+
+    fn push_children(&mut self, value: &'a Value) {
+        match value {
+            Value::Compound(x) => self.stack.push(Frame::Entries(x.values())),
+            Value::ListList(xs) => self.stack.push(Frame::List(xs.iter())),
+            Value::CompoundList(xs) => self.stack.push(Frame::Compounds(xs.iter())),
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Iterator for Recursive<'a> {
+    type Item = &'a Value;
+
+    fn next(&mut self) -> Option<&'a Value> {
+        if let Some(root) = self.root.take() {
+            self.push_children(root);
+            return Some(root);
+        }
+
+        loop {
+            let next = match self.stack.last_mut()? {
+                Frame::Entries(it) => it.next(),
+                Frame::List(it) => it.next(),
+                Frame::Compounds(it) => {
+                    if let Some(c) = it.next() {
+                        self.stack.push(Frame::Entries(c.values()));
+                        continue;
+                    }
+                    None
+                }
+            };
+
+            match next {
+                Some(value) => {
+                    self.push_children(value);
+                    return Some(value);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Depth-first iterator over compounds in a value, with their paths.
+///
+/// Created by `Value::compounds`.
+#[derive(Debug, Clone)]
+pub struct Compounds<'a> {
+    root: Option<&'a Value>,
+    stack: Vec<(String, PathFrame<'a>)>,
+}
+
+#[derive(Debug, Clone)]
+enum PathFrame<'a> {
+    Entries(btree_map::Iter<'a, String, Value>),
+    List(iter::Enumerate<slice::Iter<'a, Value>>),
+    Compounds(iter::Enumerate<slice::Iter<'a, Compound>>),
+}
+
+impl<'a> Compounds<'a> {
+    pub(crate) fn new(root: &'a Value) -> Compounds<'a> {
+        Compounds {
+            root: Some(root),
+            stack: Vec::new(),
+        }
+    }
+
+    fn push_children(&mut self, path: String, value: &'a Value) {
+        match value {
+            Value::Compound(x) => self.stack.push((path, PathFrame::Entries(x.iter()))),
+            Value::ListList(xs) => self.stack.push((path, PathFrame::List(xs.iter().enumerate()))),
+            Value::CompoundList(xs) => self.stack.push((path, PathFrame::Compounds(xs.iter().enumerate()))),
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Iterator for Compounds<'a> {
+    type Item = (String, &'a Compound);
+
+    fn next(&mut self) -> Option<(String, &'a Compound)> {
+        if let Some(root) = self.root.take() {
+            self.push_children(String::new(), root);
+            if let Value::Compound(x) = root {
+                return Some((String::new(), x));
+            }
+        }
+
+        loop {
+            let (prefix, frame) = self.stack.last_mut()?;
+            let next = match frame {
+                PathFrame::Entries(it) => it.next().map(|(name, v)| {
+                    let path = if prefix.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{}.{}", prefix, name)
+                    };
+                    (path, Ok(v))
+                }),
+                PathFrame::List(it) => it.next().map(|(i, v)| (format!("{}[{}]", prefix, i), Ok(v))),
+                PathFrame::Compounds(it) => it.next().map(|(i, c)| (format!("{}[{}]", prefix, i), Err(c))),
+            };
+
+            match next {
+                Some((path, Ok(value))) => {
+                    self.push_children(path.clone(), value);
+                    if let Value::Compound(x) = value {
+                        return Some((path, x));
+                    }
+                }
+                Some((path, Err(x))) => {
+                    self.stack.push((path.clone(), PathFrame::Entries(x.iter())));
+                    return Some((path, x));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
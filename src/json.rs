@@ -0,0 +1,60 @@
+use serde_json::{Map, Number, Value as Json};
+
+use crate::{Compound, Value};
+
+/// Largest integer JSON readers can safely hold as a double (2^53 - 1).
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Compounds become objects, lists and arrays become arrays, and others become numbers or strings.
+///
+/// Longs outside ±(2^53 - 1) become strings with `L` suffix, like `"9007199254740993L"`.
+/// Non-finite floats become `null`.
+impl From<Value> for Json {
+    fn from(value: Value) -> Json {
+        match value {
+            Value::Byte(x) => Json::from(x),
+            Value::Short(x) => Json::from(x),
+            Value::Int(x) => Json::from(x),
+            Value::Long(x) => long(x),
+            Value::Float(x) => double(f64::from(x)),
+            Value::Double(x) => double(x),
+            Value::ByteArray(xs) => Json::from(xs),
+            Value::Str(x) => Json::String(x),
+            Value::Compound(x) => compound(x),
+            Value::IntArray(xs) => Json::from(xs),
+            Value::LongArray(xs) => Json::Array(xs.into_iter().map(long).collect()),
+            Value::EndList => Json::Array(Vec::new()),
+            Value::EmptyByteList => Json::Array(Vec::new()),
+            Value::ByteList(xs) => Json::from(xs),
+            Value::ShortList(xs) => Json::from(xs),
+            Value::IntList(xs) => Json::from(xs),
+            Value::LongList(xs) => Json::Array(xs.into_iter().map(long).collect()),
+            Value::FloatList(xs) => Json::Array(xs.into_iter().map(|x| double(f64::from(x))).collect()),
+            Value::DoubleList(xs) => Json::Array(xs.into_iter().map(double).collect()),
+            Value::ByteArrayList(xs) => Json::Array(xs.into_iter().map(Json::from).collect()),
+            Value::StrList(xs) => Json::from(xs),
+            Value::ListList(xs) => Json::Array(xs.into_iter().map(Json::from).collect()),
+            Value::CompoundList(xs) => Json::Array(xs.into_iter().map(compound).collect()),
+            Value::IntArrayList(xs) => Json::Array(xs.into_iter().map(Json::from).collect()),
+            Value::LongArrayList(xs) => {
+                Json::Array(xs.into_iter().map(|xs| Json::Array(xs.into_iter().map(long).collect())).collect())
+            }
+        }
+    }
+}
+
+fn long(x: i64) -> Json {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&x) {
+        Json::from(x)
+    } else {
+        Json::String(format!("{}L", x))
+    }
+}
+
+fn double(x: f64) -> Json {
+    Number::from_f64(x).map_or(Json::Null, Json::Number)
+}
+
+fn compound(x: Compound) -> Json {
+    Json::Object(x.into_iter().map(|(k, v)| (k, Json::from(v))).collect::<Map<_, _>>())
+}
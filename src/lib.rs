@@ -1,10 +1,48 @@
 #![doc(html_root_url = "https://docs.rs/raw-nbt/0.1.1")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+pub mod blockstate;
+mod builder;
+pub mod common;
+mod compound_ext;
 pub mod decode;
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod encode;
+pub mod iter;
+#[cfg(feature = "json")]
+mod json;
+mod mutf8;
+#[cfg(feature = "compression")]
+pub mod region;
+pub mod schema;
+pub mod structure;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod snbt;
+mod tag;
+#[cfg(feature = "std")]
+pub mod transform;
+mod value_ref;
+
+use alloc::borrow::Cow;
+use alloc::collections::btree_map;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, IndexMut};
 
-use std::borrow::Cow;
-use std::collections::BTreeMap;
-use std::fmt;
+pub use builder::CompoundBuilder;
+pub use compound_ext::CompoundExt;
+pub use snbt::{NonFinite, SnbtOptions};
+pub use tag::TagType;
+pub use value_ref::{CompoundRef, ValueRef};
 
 /// An NBT Value.
 #[derive(Debug, Clone, PartialEq)]
@@ -22,6 +60,10 @@ pub enum Value {
     IntArray(Vec<i32>),
     LongArray(Vec<i64>),
     // list
+    //
+    // Empty lists keep the element tag id read from the stream, so the encoder reproduces it:
+    // id 0 is `EndList`, id 1 is `EmptyByteList`, and others are the typed variant with no element.
+    /// Empty list with element tag id 0, as Minecraft writes empty lists.
     EndList,
     /// Empty byte list.
     ///
@@ -42,6 +84,16 @@ pub enum Value {
 }
 
 impl Value {
+    /// Create empty compound.
+    pub fn new_compound() -> Value {
+        Value::Compound(Compound::new())
+    }
+
+    /// Create empty list without element type, `Value::EndList`.
+    pub fn empty_list() -> Value {
+        Value::EndList
+    }
+
     pub fn byte(&self) -> Result<i8> {
         match self {
             Value::Byte(x) => Ok(*x),
@@ -49,6 +101,11 @@ impl Value {
         }
     }
 
+    /// Get byte as boolean. Nonzero is `true`.
+    pub fn bool(&self) -> Result<bool> {
+        Ok(self.byte()? != 0)
+    }
+
     pub fn short(&self) -> Result<i16> {
         match self {
             Value::Short(x) => Ok(*x),
@@ -84,6 +141,46 @@ impl Value {
         }
     }
 
+    /// Get any integer type (byte, short, int, or long) widened to i64.
+    pub fn as_i64(&self) -> Result<i64> {
+        match self {
+            Value::Byte(x) => Ok(i64::from(*x)),
+            Value::Short(x) => Ok(i64::from(*x)),
+            Value::Int(x) => Ok(i64::from(*x)),
+            Value::Long(x) => Ok(*x),
+            _ => Err(Error::InvalidType("integer", self.val_type())),
+        }
+    }
+
+    /// Get any integer type as i8, failing with `Error::Overflow` if out of range.
+    pub fn as_i8_checked(&self) -> Result<i8> {
+        let x = self.as_i64()?;
+        i8::try_from(x).map_err(|_| Error::Overflow(x, "byte"))
+    }
+
+    /// Get any integer type as i16, failing with `Error::Overflow` if out of range.
+    pub fn as_i16_checked(&self) -> Result<i16> {
+        let x = self.as_i64()?;
+        i16::try_from(x).map_err(|_| Error::Overflow(x, "short"))
+    }
+
+    /// Get any integer type as i32, failing with `Error::Overflow` if out of range.
+    pub fn as_i32_checked(&self) -> Result<i32> {
+        let x = self.as_i64()?;
+        i32::try_from(x).map_err(|_| Error::Overflow(x, "int"))
+    }
+
+    /// Get any numeric type as f64.
+    ///
+    /// Long values beyond 2^53 lose precision.
+    pub fn as_f64(&self) -> Result<f64> {
+        match self {
+            Value::Float(x) => Ok(f64::from(*x)),
+            Value::Double(x) => Ok(*x),
+            _ => self.as_i64().map(|x| x as f64).map_err(|_| Error::InvalidType("number", self.val_type())),
+        }
+    }
+
     pub fn byte_array(&self) -> Result<&Vec<i8>> {
         match self {
             Value::ByteArray(x) => Ok(x),
@@ -91,6 +188,27 @@ impl Value {
         }
     }
 
+    /// Get byte array as unsigned bytes.
+    ///
+    /// This reinterprets the same memory, so it is zero-copy, and each byte keeps its bit pattern, like `x as u8`.
+    pub fn byte_array_u8(&self) -> Result<&[u8]> {
+        let xs = self.byte_array()?;
+        // SAFETY: i8 and u8 have the same size and alignment, and every bit pattern is valid for both.
+        Ok(unsafe { core::slice::from_raw_parts(xs.as_ptr() as *const u8, xs.len()) })
+    }
+
+    /// Get byte array as UTF-8 text, failing with `Error::InvalidUtf8` if it is not.
+    pub fn byte_array_str(&self) -> Result<&str> {
+        core::str::from_utf8(self.byte_array_u8()?).map_err(Error::InvalidUtf8)
+    }
+
+    pub fn byte_array_mut(&mut self) -> Result<&mut Vec<i8>> {
+        match self {
+            Value::ByteArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte array", self.val_type())),
+        }
+    }
+
     pub fn str(&self) -> Result<&str> {
         match self {
             Value::Str(x) => Ok(x),
@@ -98,6 +216,13 @@ impl Value {
         }
     }
 
+    pub fn str_mut(&mut self) -> Result<&mut String> {
+        match self {
+            Value::Str(x) => Ok(x),
+            _ => Err(Error::InvalidType("str", self.val_type())),
+        }
+    }
+
     pub fn compound(&self) -> Result<&Compound> {
         match self {
             Value::Compound(x) => Ok(x),
@@ -105,11 +230,334 @@ impl Value {
         }
     }
 
+    pub fn compound_mut(&mut self) -> Result<&mut Compound> {
+        match self {
+            Value::Compound(x) => Ok(x),
+            _ => Err(Error::InvalidType("compound", self.val_type())),
+        }
+    }
+
+    /// Iterate entries of compound.
+    pub fn entries(&self) -> Result<btree_map::Iter<'_, String, Value>> {
+        Ok(self.compound()?.iter())
+    }
+
+    /// Iterate entries of compound mutably.
+    pub fn entries_mut(&mut self) -> Result<btree_map::IterMut<'_, String, Value>> {
+        Ok(self.compound_mut()?.iter_mut())
+    }
+
+    /// Consume compound and iterate its entries by value, in key order.
+    pub fn into_entries(self) -> Result<btree_map::IntoIter<String, Value>> {
+        Ok(self.into_compound()?.into_iter())
+    }
+
+    /// Find all entries named `key` anywhere in the tree.
+    ///
+    /// Each result has a path in `path` syntax, like `Inventory[3].id`, and the entry value.
+    pub fn find_all<'a>(&'a self, key: &str) -> Vec<(String, &'a Value)> {
+        let mut found = Vec::new();
+        self.find_all_in(key, &mut String::new(), &mut found);
+        found
+    }
+
+    fn find_all_in<'a>(&'a self, key: &str, prefix: &mut String, found: &mut Vec<(String, &'a Value)>) {
+        match self {
+            Value::Compound(x) => find_all_in_compound(x, key, prefix, found),
+            Value::ListList(xs) => {
+                for (i, x) in xs.iter().enumerate() {
+                    let len = prefix.len();
+                    prefix.push_str(&format!("[{}]", i));
+                    x.find_all_in(key, prefix, found);
+                    prefix.truncate(len);
+                }
+            }
+            Value::CompoundList(xs) => {
+                for (i, x) in xs.iter().enumerate() {
+                    let len = prefix.len();
+                    prefix.push_str(&format!("[{}]", i));
+                    find_all_in_compound(x, key, prefix, found);
+                    prefix.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Collect all strings in the tree, both `Str` values and `StrList` elements.
+    ///
+    /// Each result has a path in `path` syntax, like `Sign.Text1` or `pages[2]`, in the order of `find_all`.
+    pub fn collect_strings(&self) -> Vec<(String, &str)> {
+        let mut found = Vec::new();
+        self.collect_strings_in(&mut String::new(), &mut found);
+        found
+    }
+
+    fn collect_strings_in<'a>(&'a self, prefix: &mut String, found: &mut Vec<(String, &'a str)>) {
+        match self {
+            Value::Str(s) => found.push((prefix.clone(), s)),
+            Value::StrList(xs) => {
+                for (i, s) in xs.iter().enumerate() {
+                    found.push((format!("{}[{}]", prefix, i), s));
+                }
+            }
+            Value::Compound(x) => collect_strings_in_compound(x, prefix, found),
+            Value::ListList(xs) => {
+                for (i, x) in xs.iter().enumerate() {
+                    let len = prefix.len();
+                    prefix.push_str(&format!("[{}]", i));
+                    x.collect_strings_in(prefix, found);
+                    prefix.truncate(len);
+                }
+            }
+            Value::CompoundList(xs) => {
+                for (i, x) in xs.iter().enumerate() {
+                    let len = prefix.len();
+                    prefix.push_str(&format!("[{}]", i));
+                    collect_strings_in_compound(x, prefix, found);
+                    prefix.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Get list element at `index`, for any list variant.
+    ///
+    /// `ListList` elements are borrowed. Other elements are not stored as `Value`, so they are returned owned;
+    /// this clones the whole compound for `CompoundList`, where `path` or `compound_list` avoid the copy.
+    pub fn list_get(&self, index: usize) -> Result<Cow<'_, Value>> {
+        fn get<T: Clone>(xs: &[T], index: usize, f: fn(T) -> Value) -> Result<Cow<'static, Value>> {
+            match xs.get(index) {
+                Some(x) => Ok(Cow::Owned(f(x.clone()))),
+                None => Err(Error::IndexOutOfRange(index, xs.len())),
+            }
+        }
+
+        match self {
+            Value::EndList | Value::EmptyByteList => Err(Error::IndexOutOfRange(index, 0)),
+            Value::ByteList(xs) => get(xs, index, Value::Byte),
+            Value::ShortList(xs) => get(xs, index, Value::Short),
+            Value::IntList(xs) => get(xs, index, Value::Int),
+            Value::LongList(xs) => get(xs, index, Value::Long),
+            Value::FloatList(xs) => get(xs, index, Value::Float),
+            Value::DoubleList(xs) => get(xs, index, Value::Double),
+            Value::ByteArrayList(xs) => get(xs, index, Value::ByteArray),
+            Value::StrList(xs) => get(xs, index, Value::Str),
+            Value::ListList(xs) => xs.get(index).map(Cow::Borrowed).ok_or(Error::IndexOutOfRange(index, xs.len())),
+            Value::CompoundList(xs) => get(xs, index, Value::Compound),
+            Value::IntArrayList(xs) => get(xs, index, Value::IntArray),
+            Value::LongArrayList(xs) => get(xs, index, Value::LongArray),
+            _ => Err(Error::InvalidType("list", self.val_type())),
+        }
+    }
+
+    /// Get the only element of a one-element list, for any list variant.
+    ///
+    /// Elements are borrowed or owned as in `list_get`.
+    /// Fails with `Error::InvalidLength` if the list has another length, or `Error::InvalidType` if not a list.
+    pub fn single(&self) -> Result<Cow<'_, Value>> {
+        if !self.is_list() {
+            return Err(Error::InvalidType("list", self.val_type()));
+        }
+        match self.len() {
+            Some(1) => self.list_get(0),
+            len => Err(Error::InvalidLength(1, len.unwrap_or(0))),
+        }
+    }
+
+    /// Get the first element of any list variant as an owned value.
+    ///
+    /// Fails with `Error::NotFound` if the list is empty, or `Error::InvalidType` if not a list.
+    pub fn list_first(&self) -> Result<Value> {
+        match self.list_get(0) {
+            Ok(x) => Ok(x.into_owned()),
+            Err(Error::IndexOutOfRange(..)) => Err(Error::NotFound("[0]".to_string())),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Iterate this value and all nested values depth-first.
+    ///
+    /// Compound entries and `ListList` elements are visited.
+    /// Compounds in `CompoundList` are not `Value`, so only their entries are visited.
+    /// Elements of other lists and arrays are not visited separately.
+    pub fn iter_recursive(&self) -> iter::Recursive<'_> {
+        iter::Recursive::new(self)
+    }
+
+    /// Iterate all compounds in this value depth-first, including this value and elements of `CompoundList`.
+    ///
+    /// Each compound comes with its path in `path` syntax, like `Level.Entities[0]`. The root has an empty path.
+    pub fn compounds(&self) -> iter::Compounds<'_> {
+        iter::Compounds::new(self)
+    }
+
+    /// Fold over this value and all nested values depth-first, without allocation.
+    ///
+    /// Values are visited in the same order as `iter_recursive`.
+    pub fn fold<B, F: FnMut(B, &Value) -> B>(&self, init: B, mut f: F) -> B {
+        self.fold_with(init, &mut f)
+    }
+
+    /// Count tags in the tree by type, including this value and list elements.
+    pub fn type_histogram(&self) -> BTreeMap<TagType, usize> {
+        self.fold(BTreeMap::new(), |mut counts, v| {
+            *counts.entry(v.tag_type()).or_insert(0) += 1;
+            match v.list_element_type() {
+                // Elements of ListList are visited by fold itself.
+                Some(TagType::List) | None => {}
+                Some(tag) => *counts.entry(tag).or_insert(0) += v.len().unwrap_or(0),
+            }
+            counts
+        })
+    }
+
+    fn fold_with<B, F: FnMut(B, &Value) -> B>(&self, init: B, f: &mut F) -> B {
+        let acc = f(init, self);
+        match self {
+            Value::Compound(x) => x.values().fold(acc, |acc, v| v.fold_with(acc, f)),
+            Value::ListList(xs) => xs.iter().fold(acc, |acc, v| v.fold_with(acc, f)),
+            Value::CompoundList(xs) => xs.iter().flat_map(|c| c.values()).fold(acc, |acc, v| v.fold_with(acc, f)),
+            _ => acc,
+        }
+    }
+
     /// Get value of compound.
     pub fn get(&self, name: &str) -> Result<&Value> {
         self.compound()?.get(name).ok_or_else(|| Error::NotFound(name.to_string()))
     }
 
+    /// Get compound entry, with `None` for a missing key.
+    ///
+    /// Unlike `get`, only a non-compound value is an error.
+    pub fn get_opt(&self, name: &str) -> Result<Option<&Value>> {
+        Ok(self.compound()?.get(name))
+    }
+
+    /// Insert value into compound, returning the old value if exists.
+    pub fn insert(&mut self, name: impl Into<String>, value: Value) -> Result<Option<Value>> {
+        Ok(self.compound_mut()?.insert(name.into(), value))
+    }
+
+    /// Get entry of compound for in-place manipulation.
+    ///
+    /// `or_insert` returns `&mut Value`, so nested entries can be chained.
+    pub fn entry(&mut self, name: impl Into<String>) -> Result<btree_map::Entry<'_, String, Value>> {
+        Ok(self.compound_mut()?.entry(name.into()))
+    }
+
+    /// Remove value from compound, returning it if exists.
+    pub fn remove(&mut self, name: &str) -> Result<Option<Value>> {
+        Ok(self.compound_mut()?.remove(name))
+    }
+
+    /// Remove compound entries for which `f(name, value)` is `false`, anywhere in the tree.
+    ///
+    /// Kept entries are visited before their nested entries, so removing a compound skips its contents.
+    /// Compounds in lists are pruned too. Other values are left as is.
+    pub fn retain<F: FnMut(&str, &Value) -> bool>(&mut self, mut f: F) {
+        self.retain_in(&mut f);
+    }
+
+    fn retain_in<F: FnMut(&str, &Value) -> bool>(&mut self, f: &mut F) {
+        match self {
+            Value::Compound(x) => retain_in_compound(x, f),
+            Value::ListList(xs) => xs.iter_mut().for_each(|x| x.retain_in(f)),
+            Value::CompoundList(xs) => xs.iter_mut().for_each(|x| retain_in_compound(x, f)),
+            _ => {}
+        }
+    }
+
+    /// Check compound has the field.
+    pub fn contains_key(&self, name: &str) -> Result<bool> {
+        Ok(self.compound()?.contains_key(name))
+    }
+
+    /// Get value by dotted path of compound keys, like `Data.Player.Health`.
+    ///
+    /// A key may be followed by list indices, like `Data.Inventory[0].id`.
+    /// The path cannot end with an index into a compound list, because its elements are not stored as `Value`.
+    pub fn path(&self, path: &str) -> Result<&Value> {
+        let mut node = PathNode::Value(self);
+
+        for segment in path.split('.') {
+            let not_found = || Error::NotFound(segment.to_string());
+            let (name, indices) = split_segment(segment)?;
+
+            node = PathNode::Value(node.compound()?.get(name).ok_or_else(not_found)?);
+
+            for index in indices {
+                node = match node {
+                    PathNode::Value(Value::ListList(xs)) => PathNode::Value(xs.get(index).ok_or_else(not_found)?),
+                    PathNode::Value(Value::CompoundList(xs)) => PathNode::Compound(xs.get(index).ok_or_else(not_found)?),
+                    PathNode::Value(v) => return Err(Error::InvalidType("list list", v.val_type())),
+                    PathNode::Compound(_) => return Err(Error::InvalidType("list list", "compound")),
+                };
+            }
+        }
+
+        match node {
+            PathNode::Value(v) => Ok(v),
+            PathNode::Compound(_) => Err(Error::InvalidType("list list", "compound list")),
+        }
+    }
+
+    /// Set value at dotted path, in the same syntax as `path`, returning the old value if exists.
+    ///
+    /// With `create`, missing compounds on the way are created. Otherwise they are `Error::NotFound`.
+    /// Indices must be in range, and an element of a compound list can only be replaced by a compound.
+    pub fn set_path(&mut self, path: &str, value: Value, create: bool) -> Result<Option<Value>> {
+        let segments: Vec<&str> = path.split('.').collect();
+        let mut node = PathNodeMut::Value(self);
+
+        for (i, segment) in segments.iter().enumerate() {
+            let not_found = || Error::NotFound(segment.to_string());
+            let (name, indices) = split_segment(segment)?;
+            let compound = node.into_compound()?;
+            let last_segment = i + 1 == segments.len();
+
+            if last_segment && indices.is_empty() {
+                return Ok(compound.insert(name.to_string(), value));
+            }
+
+            let child = if create && indices.is_empty() {
+                compound.entry(name.to_string()).or_insert_with(Value::new_compound)
+            } else {
+                compound.get_mut(name).ok_or_else(not_found)?
+            };
+            node = PathNodeMut::Value(child);
+
+            for (j, &index) in indices.iter().enumerate() {
+                let last_index = last_segment && j + 1 == indices.len();
+                node = match node {
+                    PathNodeMut::Value(Value::ListList(xs)) => {
+                        let slot = xs.get_mut(index).ok_or_else(not_found)?;
+                        if last_index {
+                            if !value.is_list() {
+                                return Err(Error::InvalidType("list", value.val_type()));
+                            }
+                            return Ok(Some(core::mem::replace(slot, value)));
+                        }
+                        PathNodeMut::Value(slot)
+                    }
+                    PathNodeMut::Value(Value::CompoundList(xs)) => {
+                        let slot = xs.get_mut(index).ok_or_else(not_found)?;
+                        if last_index {
+                            let value = value.into_compound()?;
+                            return Ok(Some(Value::Compound(core::mem::replace(slot, value))));
+                        }
+                        PathNodeMut::Compound(slot)
+                    }
+                    PathNodeMut::Value(v) => return Err(Error::InvalidType("list list", v.val_type())),
+                    PathNodeMut::Compound(_) => return Err(Error::InvalidType("list list", "compound")),
+                };
+            }
+        }
+
+        unreachable!("path has at least one segment")
+    }
+
     pub fn int_array(&self) -> Result<&Vec<i32>> {
         match self {
             Value::IntArray(x) => Ok(x),
@@ -117,6 +565,27 @@ impl Value {
         }
     }
 
+    pub fn int_array_mut(&mut self) -> Result<&mut Vec<i32>> {
+        match self {
+            Value::IntArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("int array", self.val_type())),
+        }
+    }
+
+    /// Get UUID stored as int array of 4 elements, most significant first.
+    pub fn uuid(&self) -> Result<u128> {
+        let xs = self.int_array()?;
+        if xs.len() != 4 {
+            return Err(Error::InvalidLength(4, xs.len()));
+        }
+        Ok(xs.iter().fold(0u128, |acc, &x| acc << 32 | u128::from(x as u32)))
+    }
+
+    /// Create int array of 4 elements representing UUID, most significant first.
+    pub fn from_uuid(uuid: u128) -> Value {
+        Value::IntArray((0..4).rev().map(|i| (uuid >> (32 * i)) as u32 as i32).collect())
+    }
+
     pub fn long_array(&self) -> Result<&Vec<i64>> {
         match self {
             Value::LongArray(x) => Ok(x),
@@ -124,7 +593,14 @@ impl Value {
         }
     }
 
-    pub fn byte_list(&self) -> Result<Cow<Vec<i8>>> {
+    pub fn long_array_mut(&mut self) -> Result<&mut Vec<i64>> {
+        match self {
+            Value::LongArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("long array", self.val_type())),
+        }
+    }
+
+    pub fn byte_list(&self) -> Result<Cow<'_, Vec<i8>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -133,7 +609,7 @@ impl Value {
         }
     }
 
-    pub fn short_list(&self) -> Result<Cow<Vec<i16>>> {
+    pub fn short_list(&self) -> Result<Cow<'_, Vec<i16>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -142,7 +618,7 @@ impl Value {
         }
     }
 
-    pub fn int_list(&self) -> Result<Cow<Vec<i32>>> {
+    pub fn int_list(&self) -> Result<Cow<'_, Vec<i32>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -151,7 +627,7 @@ impl Value {
         }
     }
 
-    pub fn long_list(&self) -> Result<Cow<Vec<i64>>> {
+    pub fn long_list(&self) -> Result<Cow<'_, Vec<i64>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -160,7 +636,7 @@ impl Value {
         }
     }
 
-    pub fn float_list(&self) -> Result<Cow<Vec<f32>>> {
+    pub fn float_list(&self) -> Result<Cow<'_, Vec<f32>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -169,7 +645,7 @@ impl Value {
         }
     }
 
-    pub fn double_list(&self) -> Result<Cow<Vec<f64>>> {
+    pub fn double_list(&self) -> Result<Cow<'_, Vec<f64>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -178,7 +654,7 @@ impl Value {
         }
     }
 
-    pub fn byte_array_list(&self) -> Result<Cow<Vec<Vec<i8>>>> {
+    pub fn byte_array_list(&self) -> Result<Cow<'_, Vec<Vec<i8>>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -187,7 +663,7 @@ impl Value {
         }
     }
 
-    pub fn str_list(&self) -> Result<Cow<Vec<String>>> {
+    pub fn str_list(&self) -> Result<Cow<'_, Vec<String>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -196,7 +672,7 @@ impl Value {
         }
     }
 
-    pub fn list_list(&self) -> Result<Cow<Vec<Value>>> {
+    pub fn list_list(&self) -> Result<Cow<'_, Vec<Value>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -205,7 +681,7 @@ impl Value {
         }
     }
 
-    pub fn compound_list(&self) -> Result<Cow<Vec<Compound>>> {
+    pub fn compound_list(&self) -> Result<Cow<'_, Vec<Compound>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -214,7 +690,7 @@ impl Value {
         }
     }
 
-    pub fn int_array_list(&self) -> Result<Cow<Vec<Vec<i32>>>> {
+    pub fn int_array_list(&self) -> Result<Cow<'_, Vec<Vec<i32>>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -223,7 +699,7 @@ impl Value {
         }
     }
 
-    pub fn long_array_list(&self) -> Result<Cow<Vec<Vec<i64>>>> {
+    pub fn long_array_list(&self) -> Result<Cow<'_, Vec<Vec<i64>>>> {
         match self {
             Value::EndList => Ok(Cow::Owned(Vec::new())),
             Value::EmptyByteList => Ok(Cow::Owned(Vec::new())),
@@ -232,54 +708,1161 @@ impl Value {
         }
     }
 
-    fn val_type(&self) -> &'static str {
+    /// Get elements of any list as values, like `Value::Int` for elements of `IntList`.
+    ///
+    /// Each element is copied into a new `Value`.
+    pub fn to_value_list(&self) -> Result<Vec<Value>> {
+        fn wrap<T: Clone>(xs: &[T], f: fn(T) -> Value) -> Vec<Value> {
+            xs.iter().cloned().map(f).collect()
+        }
+
         match self {
-            Value::Byte(_) => "byte",
-            Value::Short(_) => "short",
-            Value::Int(_) => "int",
-            Value::Long(_) => "long",
-            Value::Float(_) => "float",
-            Value::Double(_) => "double",
-            Value::ByteArray(_) => "byte array",
-            Value::Str(_) => "str",
-            Value::Compound(_) => "compound",
-            Value::IntArray(_) => "int array",
-            Value::LongArray(_) => "long array",
-            Value::EndList => "end list",
-            Value::EmptyByteList => "byte list (empty)",
-            Value::ByteList(_) => "byte list (non-empty)",
-            Value::ShortList(_) => "short list",
-            Value::IntList(_) => "int list",
-            Value::LongList(_) => "long list",
-            Value::FloatList(_) => "float list",
-            Value::DoubleList(_) => "double list",
-            Value::ByteArrayList(_) => "byte array list",
-            Value::StrList(_) => "str list",
-            Value::ListList(_) => "list list",
-            Value::CompoundList(_) => "compound list",
-            Value::IntArrayList(_) => "int array list",
-            Value::LongArrayList(_) => "long array list",
+            Value::EndList | Value::EmptyByteList => Ok(Vec::new()),
+            Value::ByteList(xs) => Ok(wrap(xs, Value::Byte)),
+            Value::ShortList(xs) => Ok(wrap(xs, Value::Short)),
+            Value::IntList(xs) => Ok(wrap(xs, Value::Int)),
+            Value::LongList(xs) => Ok(wrap(xs, Value::Long)),
+            Value::FloatList(xs) => Ok(wrap(xs, Value::Float)),
+            Value::DoubleList(xs) => Ok(wrap(xs, Value::Double)),
+            Value::ByteArrayList(xs) => Ok(wrap(xs, Value::ByteArray)),
+            Value::StrList(xs) => Ok(wrap(xs, Value::Str)),
+            Value::ListList(xs) => Ok(xs.clone()),
+            Value::CompoundList(xs) => Ok(wrap(xs, Value::Compound)),
+            Value::IntArrayList(xs) => Ok(wrap(xs, Value::IntArray)),
+            Value::LongArrayList(xs) => Ok(wrap(xs, Value::LongArray)),
+            _ => Err(Error::InvalidType("list", self.val_type())),
+        }
+    }
+
+    pub fn into_byte_array(self) -> Result<Vec<i8>> {
+        match self {
+            Value::ByteArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte array", self.val_type())),
+        }
+    }
+
+    pub fn into_str(self) -> Result<String> {
+        match self {
+            Value::Str(x) => Ok(x),
+            _ => Err(Error::InvalidType("str", self.val_type())),
+        }
+    }
+
+    pub fn into_compound(self) -> Result<Compound> {
+        match self {
+            Value::Compound(x) => Ok(x),
+            _ => Err(Error::InvalidType("compound", self.val_type())),
+        }
+    }
+
+    pub fn into_int_array(self) -> Result<Vec<i32>> {
+        match self {
+            Value::IntArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("int array", self.val_type())),
+        }
+    }
+
+    pub fn into_long_array(self) -> Result<Vec<i64>> {
+        match self {
+            Value::LongArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("long array", self.val_type())),
+        }
+    }
+
+    pub fn into_byte_list(self) -> Result<Vec<i8>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::ByteList(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte list", self.val_type())),
+        }
+    }
+
+    pub fn into_short_list(self) -> Result<Vec<i16>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::ShortList(x) => Ok(x),
+            _ => Err(Error::InvalidType("short list", self.val_type())),
+        }
+    }
+
+    pub fn into_int_list(self) -> Result<Vec<i32>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::IntList(x) => Ok(x),
+            _ => Err(Error::InvalidType("int list", self.val_type())),
+        }
+    }
+
+    pub fn into_long_list(self) -> Result<Vec<i64>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::LongList(x) => Ok(x),
+            _ => Err(Error::InvalidType("long list", self.val_type())),
+        }
+    }
+
+    pub fn into_float_list(self) -> Result<Vec<f32>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::FloatList(x) => Ok(x),
+            _ => Err(Error::InvalidType("float list", self.val_type())),
+        }
+    }
+
+    pub fn into_double_list(self) -> Result<Vec<f64>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::DoubleList(x) => Ok(x),
+            _ => Err(Error::InvalidType("double list", self.val_type())),
+        }
+    }
+
+    pub fn into_byte_array_list(self) -> Result<Vec<Vec<i8>>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::ByteArrayList(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte array list", self.val_type())),
+        }
+    }
+
+    pub fn into_str_list(self) -> Result<Vec<String>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::StrList(x) => Ok(x),
+            _ => Err(Error::InvalidType("str list", self.val_type())),
+        }
+    }
+
+    pub fn into_list_list(self) -> Result<Vec<Value>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::ListList(x) => Ok(x),
+            _ => Err(Error::InvalidType("list list", self.val_type())),
         }
     }
+
+    pub fn into_compound_list(self) -> Result<Vec<Compound>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::CompoundList(x) => Ok(x),
+            _ => Err(Error::InvalidType("compound list", self.val_type())),
+        }
+    }
+
+    pub fn into_int_array_list(self) -> Result<Vec<Vec<i32>>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::IntArrayList(x) => Ok(x),
+            _ => Err(Error::InvalidType("int array list", self.val_type())),
+        }
+    }
+
+    pub fn into_long_array_list(self) -> Result<Vec<Vec<i64>>> {
+        match self {
+            Value::EndList => Ok(Vec::new()),
+            Value::EmptyByteList => Ok(Vec::new()),
+            Value::LongArrayList(x) => Ok(x),
+            _ => Err(Error::InvalidType("long array list", self.val_type())),
+        }
+    }
+
+    /// Byte length of this value written as a named tag by `encode::Encoder::write`, without writing it.
+    ///
+    /// The length is computed even for values the encoder rejects, such as too long strings.
+    pub fn serialized_len(&self, name: &str) -> usize {
+        1 + str_len(name) + self.payload_len()
+    }
+
+    fn payload_len(&self) -> usize {
+        // list header is element tag id and length
+        const LIST: usize = 5;
+
+        match self {
+            Value::Byte(_) => 1,
+            Value::Short(_) => 2,
+            Value::Int(_) | Value::Float(_) => 4,
+            Value::Long(_) | Value::Double(_) => 8,
+            Value::ByteArray(xs) => 4 + xs.len(),
+            Value::Str(x) => str_len(x),
+            Value::Compound(x) => compound_len(x),
+            Value::IntArray(xs) => 4 + 4 * xs.len(),
+            Value::LongArray(xs) => 4 + 8 * xs.len(),
+            Value::EndList | Value::EmptyByteList => LIST,
+            Value::ByteList(xs) => LIST + xs.len(),
+            Value::ShortList(xs) => LIST + 2 * xs.len(),
+            Value::IntList(xs) => LIST + 4 * xs.len(),
+            Value::LongList(xs) => LIST + 8 * xs.len(),
+            Value::FloatList(xs) => LIST + 4 * xs.len(),
+            Value::DoubleList(xs) => LIST + 8 * xs.len(),
+            Value::ByteArrayList(xs) => LIST + xs.iter().map(|x| 4 + x.len()).sum::<usize>(),
+            Value::StrList(xs) => LIST + xs.iter().map(|x| str_len(x)).sum::<usize>(),
+            Value::ListList(xs) => LIST + xs.iter().map(Value::payload_len).sum::<usize>(),
+            Value::CompoundList(xs) => LIST + xs.iter().map(compound_len).sum::<usize>(),
+            Value::IntArrayList(xs) => LIST + xs.iter().map(|x| 4 + 4 * x.len()).sum::<usize>(),
+            Value::LongArrayList(xs) => LIST + xs.iter().map(|x| 4 + 8 * x.len()).sum::<usize>(),
+        }
+    }
+
+    /// Write value as SNBT. Same output as `Display`, where `pretty` corresponds to `{:#}`.
+    pub fn write_snbt<W: fmt::Write>(&self, w: &mut W, pretty: bool) -> fmt::Result {
+        snbt::SnbtWriter::new(w, pretty).write_value(self)
+    }
+
+    /// Write value as SNBT with `options`.
+    ///
+    /// A non-finite float with `NonFinite::Error` fails with `fmt::Error`. Use `to_snbt` to get the value.
+    pub fn write_snbt_with<W: fmt::Write>(&self, w: &mut W, options: SnbtOptions) -> fmt::Result {
+        snbt::SnbtWriter::with_options(w, options).write_value(self)
+    }
+
+    /// Format value as SNBT with `options`.
+    ///
+    /// A non-finite float with `NonFinite::Error` fails with `Error::NonFinite`.
+    pub fn to_snbt(&self, options: SnbtOptions) -> Result<String> {
+        let mut s = String::new();
+        match self.write_snbt_with(&mut s, options) {
+            Ok(()) => Ok(s),
+            Err(fmt::Error) => Err(Error::NonFinite(self.find_non_finite().unwrap_or(f64::NAN))),
+        }
+    }
+
+    /// Parse SNBT text stored in a `Str` value, like `{Count:1b,id:"stone"}` in some command block data.
+    ///
+    /// Fails with `ParseError::NotStr` if value is not `Str`, or with the SNBT parse error of its contents.
+    pub fn parse_inner_snbt(&self) -> decode::Result<Value> {
+        match self {
+            Value::Str(s) => decode::from_snbt(s),
+            _ => Err(decode::ParseError::NotStr(self.tag_type())),
+        }
+    }
+
+    fn find_non_finite(&self) -> Option<f64> {
+        let non_finite = |x: &f64| !x.is_finite();
+        self.iter_recursive().find_map(|v| match v {
+            Value::Float(x) => Some(f64::from(*x)).filter(non_finite),
+            Value::Double(x) => Some(*x).filter(non_finite),
+            Value::FloatList(xs) => xs.iter().map(|&x| f64::from(x)).find(non_finite),
+            Value::DoubleList(xs) => xs.iter().copied().find(non_finite),
+            _ => None,
+        })
+    }
+
+    /// Display as SNBT, but with lists and arrays longer than `max_len` abbreviated like `[... 4096 ints ...]`.
+    ///
+    /// For debug output of large files. `{:#}` gives pretty output like `Display`.
+    pub fn summary(&self, max_len: usize) -> Summary<'_> {
+        Summary { value: self, max_len }
+    }
+
+    /// Get tag type. All list variants are `TagType::List`.
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            Value::Byte(_) => TagType::Byte,
+            Value::Short(_) => TagType::Short,
+            Value::Int(_) => TagType::Int,
+            Value::Long(_) => TagType::Long,
+            Value::Float(_) => TagType::Float,
+            Value::Double(_) => TagType::Double,
+            Value::ByteArray(_) => TagType::ByteArray,
+            Value::Str(_) => TagType::Str,
+            Value::Compound(_) => TagType::Compound,
+            Value::IntArray(_) => TagType::IntArray,
+            Value::LongArray(_) => TagType::LongArray,
+            _ => TagType::List,
+        }
+    }
+
+    /// Get `Some(self)` if tag type is `ty`, for filtering without building an `Error`.
+    ///
+    /// All list variants match `TagType::List`, like `tag_type`.
+    pub fn as_type(&self, ty: TagType) -> Option<&Value> {
+        if self.tag_type() == ty {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Get element count of list, array, or compound. `None` for other types.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Value::ByteArray(x) => Some(x.len()),
+            Value::Compound(x) => Some(x.len()),
+            Value::IntArray(x) => Some(x.len()),
+            Value::LongArray(x) => Some(x.len()),
+            Value::EndList => Some(0),
+            Value::EmptyByteList => Some(0),
+            Value::ByteList(x) => Some(x.len()),
+            Value::ShortList(x) => Some(x.len()),
+            Value::IntList(x) => Some(x.len()),
+            Value::LongList(x) => Some(x.len()),
+            Value::FloatList(x) => Some(x.len()),
+            Value::DoubleList(x) => Some(x.len()),
+            Value::ByteArrayList(x) => Some(x.len()),
+            Value::StrList(x) => Some(x.len()),
+            Value::ListList(x) => Some(x.len()),
+            Value::CompoundList(x) => Some(x.len()),
+            Value::IntArrayList(x) => Some(x.len()),
+            Value::LongArrayList(x) => Some(x.len()),
+            _ => None,
+        }
+    }
+
+    /// Check list, array, or compound has no element. `None` for other types.
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|n| n == 0)
+    }
+
+    /// Get element type of list. `None` for non-list types.
+    ///
+    /// `EndList` and `EmptyByteList` also give `None`, because they have no reliable element type.
+    pub fn list_element_type(&self) -> Option<TagType> {
+        match self {
+            Value::ByteList(_) => Some(TagType::Byte),
+            Value::ShortList(_) => Some(TagType::Short),
+            Value::IntList(_) => Some(TagType::Int),
+            Value::LongList(_) => Some(TagType::Long),
+            Value::FloatList(_) => Some(TagType::Float),
+            Value::DoubleList(_) => Some(TagType::Double),
+            Value::ByteArrayList(_) => Some(TagType::ByteArray),
+            Value::StrList(_) => Some(TagType::Str),
+            Value::ListList(_) => Some(TagType::List),
+            Value::CompoundList(_) => Some(TagType::Compound),
+            Value::IntArrayList(_) => Some(TagType::IntArray),
+            Value::LongArrayList(_) => Some(TagType::LongArray),
+            _ => None,
+        }
+    }
+
+    /// `Some(())` if value is a list without elements, whatever element type it declares.
+    pub fn as_empty_list(&self) -> Option<()> {
+        if self.is_list() && self.is_empty() == Some(true) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Reinterpret list as a list of `expected` element type.
+    ///
+    /// Any empty list, including `EndList` and `EmptyByteList`, becomes an empty list of `expected` type.
+    /// Non-empty lists are returned as is if their element type matches.
+    pub fn coerce_list(&self, expected: TagType) -> Result<Value> {
+        if self.as_empty_list().is_some() {
+            return Ok(Value::empty_list_of(expected));
+        }
+
+        if self.list_element_type() == Some(expected) {
+            Ok(self.clone())
+        } else {
+            Err(Error::InvalidType(list_type_name(expected), self.val_type()))
+        }
+    }
+
+    /// Convert numeric list to the next wider element type.
+    ///
+    /// Byte, short, and int lists become short, int, and long lists, and a float list becomes a double list.
+    /// Other lists, including long and double lists, are `Error::InvalidType`.
+    pub fn widen_numeric_list(&self) -> Result<Value> {
+        let target = match self {
+            Value::EmptyByteList | Value::ByteList(_) => TagType::Short,
+            Value::ShortList(_) => TagType::Int,
+            Value::IntList(_) => TagType::Long,
+            Value::FloatList(_) => TagType::Double,
+            _ => return Err(Error::InvalidType("byte, short, int, or float list", self.val_type())),
+        };
+        self.coerce_list_to(target)
+    }
+
+    /// Convert numeric list to a list of `target` element type.
+    ///
+    /// Integer lists convert to any integer type, failing with `Error::Overflow` if an element doesn't fit.
+    /// Conversions to float and double are allowed only where they are exact: from float, byte, and short lists,
+    /// and for double also from int lists. Empty lists convert like `coerce_list`.
+    pub fn coerce_list_to(&self, target: TagType) -> Result<Value> {
+        if self.as_empty_list().is_some() || self.list_element_type() == Some(target) {
+            return self.coerce_list(target);
+        }
+
+        let mismatch = || Error::InvalidType(list_type_name(target), self.val_type());
+        let ints: Option<Vec<i64>> = match self {
+            Value::ByteList(xs) => Some(xs.iter().map(|&x| i64::from(x)).collect()),
+            Value::ShortList(xs) => Some(xs.iter().map(|&x| i64::from(x)).collect()),
+            Value::IntList(xs) => Some(xs.iter().map(|&x| i64::from(x)).collect()),
+            Value::LongList(xs) => Some(xs.clone()),
+            _ => None,
+        };
+
+        fn narrow<T: TryFrom<i64>>(xs: &[i64], to_be: &'static str) -> Result<Vec<T>> {
+            xs.iter().map(|&x| T::try_from(x).map_err(|_| Error::Overflow(x, to_be))).collect()
+        }
+
+        match (target, self) {
+            (TagType::Byte, _) => Ok(Value::ByteList(narrow(&ints.ok_or_else(mismatch)?, "byte")?)),
+            (TagType::Short, _) => Ok(Value::ShortList(narrow(&ints.ok_or_else(mismatch)?, "short")?)),
+            (TagType::Int, _) => Ok(Value::IntList(narrow(&ints.ok_or_else(mismatch)?, "int")?)),
+            (TagType::Long, _) => Ok(Value::LongList(ints.ok_or_else(mismatch)?)),
+            (TagType::Float, Value::ByteList(_)) | (TagType::Float, Value::ShortList(_)) => {
+                Ok(Value::FloatList(ints.ok_or_else(mismatch)?.into_iter().map(|x| x as f32).collect()))
+            }
+            (TagType::Double, Value::FloatList(xs)) => {
+                Ok(Value::DoubleList(xs.iter().map(|&x| f64::from(x)).collect()))
+            }
+            (TagType::Double, Value::LongList(_)) => Err(mismatch()),
+            (TagType::Double, _) => {
+                Ok(Value::DoubleList(ints.ok_or_else(mismatch)?.into_iter().map(|x| x as f64).collect()))
+            }
+            _ => Err(mismatch()),
+        }
+    }
+
+    /// Concatenate the sublists of a `ListList` into one typed list.
+    ///
+    /// Empty sublists are ignored, and the others must share one element type.
+    /// A `ListList` with only empty sublists gives `EndList`.
+    pub fn flatten_list(&self) -> Result<Value> {
+        let xs = match self {
+            Value::ListList(xs) => xs,
+            _ => return Err(Error::InvalidType("list list", self.val_type())),
+        };
+
+        let mut flat = Value::EndList;
+        if let Some(tag) = xs.iter().find_map(Value::list_element_type) {
+            for x in xs {
+                if x.as_empty_list().is_none() && x.list_element_type() != Some(tag) {
+                    return Err(Error::InvalidType(list_type_name(tag), x.val_type()));
+                }
+                concat_list(&mut flat, x);
+            }
+        }
+        Ok(flat)
+    }
+
+    /// Get canonical representation of nested lists.
+    ///
+    /// In each `ListList`, empty sublists are coerced to the element type of the others if they all share one,
+    /// or to `EndList` if no sublist has an element type. This is applied recursively,
+    /// so sublists have uniform element access.
+    pub fn homogenize(&self) -> Value {
+        match self {
+            Value::Compound(x) => Value::Compound(homogenize_compound(x)),
+            Value::CompoundList(xs) => Value::CompoundList(xs.iter().map(homogenize_compound).collect()),
+            Value::ListList(xs) => {
+                let xs: Vec<Value> = xs.iter().map(Value::homogenize).collect();
+                let tag = match xs.iter().find_map(Value::list_element_type) {
+                    Some(tag) => tag,
+                    None => return Value::ListList(xs.iter().map(|_| Value::EndList).collect()),
+                };
+                if xs.iter().all(|x| x.as_empty_list().is_some() || x.list_element_type() == Some(tag)) {
+                    Value::ListList(xs.iter().map(|x| x.coerce_list(tag).unwrap_or_else(|_| x.clone())).collect())
+                } else {
+                    Value::ListList(xs)
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Compare structurally, treating all empty list representations as equal.
+    ///
+    /// `EndList`, `EmptyByteList`, and empty typed lists are indistinguishable here.
+    /// Derived `PartialEq` stays strict.
+    pub fn semantic_eq(&self, other: &Value) -> bool {
+        if self.as_empty_list().is_some() && other.as_empty_list().is_some() {
+            return true;
+        }
+
+        match (self, other) {
+            (Value::Compound(a), Value::Compound(b)) => compound_semantic_eq(a, b),
+            (Value::ListList(a), Value::ListList(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.semantic_eq(y))
+            }
+            (Value::CompoundList(a), Value::CompoundList(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| compound_semantic_eq(x, y))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Get a copy in canonical form, so that equivalent trees are equal and hash the same.
+    ///
+    /// These are normalized, recursively:
+    ///
+    /// - Empty lists of any element type, including `EmptyByteList`, become `EndList`.
+    /// - `-0.0` becomes `0.0`, in floats, doubles, and their lists.
+    /// - NaN becomes the `NAN` constant of its type. It is still not equal to itself, but hashes the same.
+    ///
+    /// Compound keys are already sorted. Other values, such as arrays and list element types, are kept.
+    pub fn canonicalize(&self) -> Value {
+        let mut value = self.clone();
+        value.canonicalize_in_place();
+        value
+    }
+
+    fn canonicalize_in_place(&mut self) {
+        if self.as_empty_list().is_some() {
+            *self = Value::EndList;
+            return;
+        }
+
+        match self {
+            Value::Float(x) => *x = canonical_f32(*x),
+            Value::Double(x) => *x = canonical_f64(*x),
+            Value::FloatList(xs) => xs.iter_mut().for_each(|x| *x = canonical_f32(*x)),
+            Value::DoubleList(xs) => xs.iter_mut().for_each(|x| *x = canonical_f64(*x)),
+            Value::Compound(x) => x.values_mut().for_each(Value::canonicalize_in_place),
+            Value::ListList(xs) => xs.iter_mut().for_each(Value::canonicalize_in_place),
+            Value::CompoundList(xs) => xs.iter_mut().flat_map(|x| x.values_mut()).for_each(Value::canonicalize_in_place),
+            _ => {}
+        }
+    }
+
+    /// Build a list from `elements` of one tag type, choosing the list variant by that type.
+    ///
+    /// Empty `elements` gives `Value::EndList`. A byte list is never `EmptyByteList`.
+    /// Fails with `Error::ListTypeMismatch` at the first element whose tag type differs from the first one.
+    pub fn make_list(elements: Vec<Value>) -> Result<Value> {
+        let tag = match elements.first() {
+            Some(v) => v.tag_type(),
+            None => return Ok(Value::EndList),
+        };
+        if let Some(index) = elements.iter().position(|v| v.tag_type() != tag) {
+            return Err(Error::ListTypeMismatch {
+                declared: tag,
+                index,
+                found: elements[index].tag_type(),
+            });
+        }
+
+        let list = match tag {
+            TagType::End => Value::EndList,
+            TagType::Byte => Value::ByteList(elements.iter().filter_map(|v| v.byte().ok()).collect()),
+            TagType::Short => Value::ShortList(elements.iter().filter_map(|v| v.short().ok()).collect()),
+            TagType::Int => Value::IntList(elements.iter().filter_map(|v| v.int().ok()).collect()),
+            TagType::Long => Value::LongList(elements.iter().filter_map(|v| v.long().ok()).collect()),
+            TagType::Float => Value::FloatList(elements.iter().filter_map(|v| v.float().ok()).collect()),
+            TagType::Double => Value::DoubleList(elements.iter().filter_map(|v| v.double().ok()).collect()),
+            TagType::ByteArray => {
+                Value::ByteArrayList(elements.into_iter().filter_map(|v| v.into_byte_array().ok()).collect())
+            }
+            TagType::Str => Value::StrList(elements.into_iter().filter_map(|v| v.into_str().ok()).collect()),
+            TagType::List => Value::ListList(elements),
+            TagType::Compound => {
+                Value::CompoundList(elements.into_iter().filter_map(|v| v.into_compound().ok()).collect())
+            }
+            TagType::IntArray => {
+                Value::IntArrayList(elements.into_iter().filter_map(|v| v.into_int_array().ok()).collect())
+            }
+            TagType::LongArray => {
+                Value::LongArrayList(elements.into_iter().filter_map(|v| v.into_long_array().ok()).collect())
+            }
+        };
+
+        Ok(list)
+    }
+
+    fn empty_list_of(tag: TagType) -> Value {
+        match tag {
+            TagType::End => Value::EndList,
+            TagType::Byte => Value::EmptyByteList,
+            TagType::Short => Value::ShortList(Vec::new()),
+            TagType::Int => Value::IntList(Vec::new()),
+            TagType::Long => Value::LongList(Vec::new()),
+            TagType::Float => Value::FloatList(Vec::new()),
+            TagType::Double => Value::DoubleList(Vec::new()),
+            TagType::ByteArray => Value::ByteArrayList(Vec::new()),
+            TagType::Str => Value::StrList(Vec::new()),
+            TagType::List => Value::ListList(Vec::new()),
+            TagType::Compound => Value::CompoundList(Vec::new()),
+            TagType::IntArray => Value::IntArrayList(Vec::new()),
+            TagType::LongArray => Value::LongArrayList(Vec::new()),
+        }
+    }
+
+    pub fn is_byte(&self) -> bool {
+        self.tag_type() == TagType::Byte
+    }
+
+    pub fn is_compound(&self) -> bool {
+        self.tag_type() == TagType::Compound
+    }
+
+    /// True for all list variants, including `EndList` and `EmptyByteList`.
+    pub fn is_list(&self) -> bool {
+        self.tag_type() == TagType::List
+    }
+
+    /// True for byte, short, int, long, float, and double.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.tag_type(),
+            TagType::Byte | TagType::Short | TagType::Int | TagType::Long | TagType::Float | TagType::Double
+        )
+    }
+
+    /// True for byte array, int array, and long array.
+    pub fn is_array(&self) -> bool {
+        matches!(self.tag_type(), TagType::ByteArray | TagType::IntArray | TagType::LongArray)
+    }
+
+    fn val_type(&self) -> &'static str {
+        match self {
+            Value::Byte(_) => "byte",
+            Value::Short(_) => "short",
+            Value::Int(_) => "int",
+            Value::Long(_) => "long",
+            Value::Float(_) => "float",
+            Value::Double(_) => "double",
+            Value::ByteArray(_) => "byte array",
+            Value::Str(_) => "str",
+            Value::Compound(_) => "compound",
+            Value::IntArray(_) => "int array",
+            Value::LongArray(_) => "long array",
+            Value::EndList => "end list",
+            Value::EmptyByteList => "byte list (empty)",
+            Value::ByteList(_) => "byte list (non-empty)",
+            Value::ShortList(_) => "short list",
+            Value::IntList(_) => "int list",
+            Value::LongList(_) => "long list",
+            Value::FloatList(_) => "float list",
+            Value::DoubleList(_) => "double list",
+            Value::ByteArrayList(_) => "byte array list",
+            Value::StrList(_) => "str list",
+            Value::ListList(_) => "list list",
+            Value::CompoundList(_) => "compound list",
+            Value::IntArrayList(_) => "int array list",
+            Value::LongArrayList(_) => "long array list",
+        }
+    }
+}
+
+fn find_all_in_compound<'a>(x: &'a Compound, key: &str, prefix: &mut String, found: &mut Vec<(String, &'a Value)>) {
+    for (name, value) in x {
+        let len = prefix.len();
+        if !prefix.is_empty() {
+            prefix.push('.');
+        }
+        prefix.push_str(name);
+        if name == key {
+            found.push((prefix.clone(), value));
+        }
+        value.find_all_in(key, prefix, found);
+        prefix.truncate(len);
+    }
+}
+
+fn retain_in_compound<F: FnMut(&str, &Value) -> bool>(x: &mut Compound, f: &mut F) {
+    x.retain(|name, value| f(name, value));
+    x.values_mut().for_each(|value| value.retain_in(f));
+}
+
+fn collect_strings_in_compound<'a>(x: &'a Compound, prefix: &mut String, found: &mut Vec<(String, &'a str)>) {
+    for (name, value) in x {
+        let len = prefix.len();
+        if !prefix.is_empty() {
+            prefix.push('.');
+        }
+        prefix.push_str(name);
+        value.collect_strings_in(prefix, found);
+        prefix.truncate(len);
+    }
+}
+
+fn list_or_end<T>(xs: Vec<T>, f: fn(Vec<T>) -> Value) -> Value {
+    if xs.is_empty() {
+        Value::EndList
+    } else {
+        f(xs)
+    }
+}
+
+fn compound_len(x: &Compound) -> usize {
+    x.iter().map(|(name, value)| value.serialized_len(name)).sum::<usize>() + 1
+}
+
+fn str_len(x: &str) -> usize {
+    2 + mutf8::encoded_len(x)
+}
+
+fn homogenize_compound(x: &Compound) -> Compound {
+    x.iter().map(|(k, v)| (k.clone(), v.homogenize())).collect()
+}
+
+fn compound_semantic_eq(a: &Compound, b: &Compound) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|((ka, va), (kb, vb))| ka == kb && va.semantic_eq(vb))
+}
+
+fn list_type_name(tag: TagType) -> &'static str {
+    match tag {
+        TagType::End => "end list",
+        TagType::Byte => "byte list",
+        TagType::Short => "short list",
+        TagType::Int => "int list",
+        TagType::Long => "long list",
+        TagType::Float => "float list",
+        TagType::Double => "double list",
+        TagType::ByteArray => "byte array list",
+        TagType::Str => "str list",
+        TagType::List => "list list",
+        TagType::Compound => "compound list",
+        TagType::IntArray => "int array list",
+        TagType::LongArray => "long array list",
+    }
+}
+
+/// Split path segment like `Items[0][1]` into key and indices.
+fn split_segment(segment: &str) -> Result<(&str, Vec<usize>)> {
+    let not_found = || Error::NotFound(segment.to_string());
+    let (name, mut rest) = match segment.find('[') {
+        Some(i) => segment.split_at(i),
+        None => (segment, ""),
+    };
+
+    let mut indices = Vec::new();
+    while !rest.is_empty() {
+        if !rest.starts_with('[') {
+            return Err(not_found());
+        }
+        let end = rest.find(']').ok_or_else(not_found)?;
+        indices.push(rest[1..end].parse().map_err(|_| not_found())?);
+        rest = &rest[end + 1..];
+    }
+    Ok((name, indices))
+}
+
+enum PathNode<'a> {
+    Value(&'a Value),
+    Compound(&'a Compound),
+}
+
+impl<'a> PathNode<'a> {
+    fn compound(&self) -> Result<&'a Compound> {
+        match self {
+            PathNode::Value(v) => v.compound(),
+            PathNode::Compound(c) => Ok(c),
+        }
+    }
+}
+
+enum PathNodeMut<'a> {
+    Value(&'a mut Value),
+    Compound(&'a mut Compound),
+}
+
+impl<'a> PathNodeMut<'a> {
+    fn into_compound(self) -> Result<&'a mut Compound> {
+        match self {
+            PathNodeMut::Value(v) => v.compound_mut(),
+            PathNodeMut::Compound(c) => Ok(c),
+        }
+    }
+}
+
+/// Get value of compound.
+///
+/// # Panics
+///
+/// Panics if the value is not a compound or the field is missing. Use `Value::get` to handle those cases.
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, name: &str) -> &Value {
+        match self.get(name) {
+            Ok(v) => v,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+/// Mutable access to compound entry.
+///
+/// Panics if value is not a compound or `name` is missing. Missing keys are not created; use `insert` for that.
+impl IndexMut<&str> for Value {
+    fn index_mut(&mut self, name: &str) -> &mut Value {
+        let compound = match self.compound_mut() {
+            Ok(c) => c,
+            Err(e) => panic!("{}", e),
+        };
+        match compound.get_mut(name) {
+            Some(v) => v,
+            None => panic!("{}", Error::NotFound(name.to_string())),
+        }
+    }
+}
+
+/// `Value` is `Eq` so that it can be used in hash sets, but a value containing NaN is not equal to itself.
+impl Eq for Value {}
+
+/// Floats are hashed by bit pattern, with `-0.0` hashed as `0.0` to agree with `PartialEq`.
+///
+/// NaN values with different bit patterns hash differently, though no NaN compares equal anyway.
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match self {
+            Value::Byte(x) => x.hash(state),
+            Value::Short(x) => x.hash(state),
+            Value::Int(x) => x.hash(state),
+            Value::Long(x) => x.hash(state),
+            Value::Float(x) => hash_f32(*x, state),
+            Value::Double(x) => hash_f64(*x, state),
+            Value::ByteArray(xs) => xs.hash(state),
+            Value::Str(x) => x.hash(state),
+            Value::Compound(x) => x.hash(state),
+            Value::IntArray(xs) => xs.hash(state),
+            Value::LongArray(xs) => xs.hash(state),
+            Value::EndList => {}
+            Value::EmptyByteList => {}
+            Value::ByteList(xs) => xs.hash(state),
+            Value::ShortList(xs) => xs.hash(state),
+            Value::IntList(xs) => xs.hash(state),
+            Value::LongList(xs) => xs.hash(state),
+            Value::FloatList(xs) => {
+                xs.len().hash(state);
+                xs.iter().for_each(|x| hash_f32(*x, state));
+            }
+            Value::DoubleList(xs) => {
+                xs.len().hash(state);
+                xs.iter().for_each(|x| hash_f64(*x, state));
+            }
+            Value::ByteArrayList(xs) => xs.hash(state),
+            Value::StrList(xs) => xs.hash(state),
+            Value::ListList(xs) => xs.hash(state),
+            Value::CompoundList(xs) => xs.hash(state),
+            Value::IntArrayList(xs) => xs.hash(state),
+            Value::LongArrayList(xs) => xs.hash(state),
+        }
+    }
+}
+
+fn canonical_f32(x: f32) -> f32 {
+    if x.is_nan() {
+        f32::NAN
+    } else if x == 0.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+fn canonical_f64(x: f64) -> f64 {
+    if x.is_nan() {
+        f64::NAN
+    } else if x == 0.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+fn hash_f32<H: Hasher>(x: f32, state: &mut H) {
+    let x = if x == 0.0 { 0.0f32 } else { x };
+    x.to_bits().hash(state);
+}
+
+fn hash_f64<H: Hasher>(x: f64, state: &mut H) {
+    let x = if x == 0.0 { 0.0f64 } else { x };
+    x.to_bits().hash(state);
+}
+
+impl From<i8> for Value {
+    fn from(x: i8) -> Value {
+        Value::Byte(x)
+    }
+}
+
+impl From<i16> for Value {
+    fn from(x: i16) -> Value {
+        Value::Short(x)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(x: i32) -> Value {
+        Value::Int(x)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(x: i64) -> Value {
+        Value::Long(x)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(x: f32) -> Value {
+        Value::Float(x)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Value {
+        Value::Double(x)
+    }
+}
+
+/// Empty compound.
+impl Default for Value {
+    fn default() -> Value {
+        Value::new_compound()
+    }
+}
+
+/// Format as SNBT, like `{Health:20.0f,Pos:[0.0d,64.0d,0.0d]}`.
+///
+/// The alternate flag (`{:#}`) produces indented multi-line output,
+/// and a precision like `{:.2}` applies to floats and doubles.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pretty = f.alternate();
+        let precision = f.precision();
+        snbt::SnbtWriter::new(f, pretty).precision(precision).write_value(self)
+    }
+}
+
+/// Abbreviated SNBT display. Created by `Value::summary`.
+#[derive(Debug, Clone, Copy)]
+pub struct Summary<'a> {
+    value: &'a Value,
+    max_len: usize,
+}
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pretty = f.alternate();
+        snbt::SnbtWriter::new(f, pretty).max_len(self.max_len).write_value(self.value)
+    }
+}
+
+/// Converts into `Value::Str`.
+impl From<String> for Value {
+    fn from(x: String) -> Value {
+        Value::Str(x)
+    }
+}
+
+/// Converts into `Value::Str`.
+impl From<&str> for Value {
+    fn from(x: &str) -> Value {
+        Value::Str(x.to_string())
+    }
+}
+
+/// Converts into `Value::Compound`.
+impl From<Compound> for Value {
+    fn from(x: Compound) -> Value {
+        Value::Compound(x)
+    }
+}
+
+// No `From<Vec<i8>>`: it could mean `ByteList` or `ByteArray`, and `TryFrom<Value> for Vec<i8>` reads the latter.
+
+/// Converts into `Value::ShortList`, or `Value::EndList` if empty.
+impl From<Vec<i16>> for Value {
+    fn from(xs: Vec<i16>) -> Value {
+        list_or_end(xs, Value::ShortList)
+    }
+}
+
+/// Converts into `Value::IntList`, or `Value::EndList` if empty.
+impl From<Vec<i32>> for Value {
+    fn from(xs: Vec<i32>) -> Value {
+        list_or_end(xs, Value::IntList)
+    }
+}
+
+/// Converts into `Value::LongList`, or `Value::EndList` if empty.
+impl From<Vec<i64>> for Value {
+    fn from(xs: Vec<i64>) -> Value {
+        list_or_end(xs, Value::LongList)
+    }
+}
+
+/// Converts into `Value::FloatList`, or `Value::EndList` if empty.
+impl From<Vec<f32>> for Value {
+    fn from(xs: Vec<f32>) -> Value {
+        list_or_end(xs, Value::FloatList)
+    }
+}
+
+/// Converts into `Value::DoubleList`, or `Value::EndList` if empty.
+impl From<Vec<f64>> for Value {
+    fn from(xs: Vec<f64>) -> Value {
+        list_or_end(xs, Value::DoubleList)
+    }
+}
+
+/// Converts into `Value::StrList`, or `Value::EndList` if empty.
+impl From<Vec<String>> for Value {
+    fn from(xs: Vec<String>) -> Value {
+        list_or_end(xs, Value::StrList)
+    }
+}
+
+/// Converts into `Value::CompoundList`, or `Value::EndList` if empty.
+impl From<Vec<Compound>> for Value {
+    fn from(xs: Vec<Compound>) -> Value {
+        list_or_end(xs, Value::CompoundList)
+    }
+}
+
+impl TryFrom<Value> for i8 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<i8> {
+        value.byte()
+    }
+}
+
+impl TryFrom<Value> for i16 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<i16> {
+        value.short()
+    }
+}
+
+impl TryFrom<Value> for i32 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<i32> {
+        value.int()
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<i64> {
+        value.long()
+    }
+}
+
+impl TryFrom<Value> for f32 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<f32> {
+        value.float()
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<f64> {
+        value.double()
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<String> {
+        value.into_str()
+    }
+}
+
+impl TryFrom<Value> for Vec<i8> {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Vec<i8>> {
+        value.into_byte_array()
+    }
+}
+
+/// How `merge` handles a list present in both compounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMerge {
+    /// The overlay list replaces the base list, as Minecraft's `/data merge` does.
+    Replace,
+    /// Overlay elements are appended to the base list.
+    ///
+    /// If either list is empty, the result is the other one.
+    /// If element types differ, the overlay list replaces the base list.
+    Concat,
+}
+
+/// Merge `overlay` into `base` recursively.
+///
+/// Compounds present in both are merged, lists are handled by `lists`, and other values in `overlay` win.
+pub fn merge(base: &mut Compound, overlay: &Compound, lists: ListMerge) {
+    for (name, value) in overlay {
+        match (base.get_mut(name), value) {
+            (Some(Value::Compound(b)), Value::Compound(o)) => merge(b, o, lists),
+            (Some(b), o) if lists == ListMerge::Concat && b.is_list() && o.is_list() => concat_list(b, o),
+            _ => {
+                base.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn concat_list(base: &mut Value, overlay: &Value) {
+    match (&mut *base, overlay) {
+        (_, o) if o.is_empty() == Some(true) => {}
+        (Value::ByteList(b), Value::ByteList(o)) => b.extend_from_slice(o),
+        (Value::ShortList(b), Value::ShortList(o)) => b.extend_from_slice(o),
+        (Value::IntList(b), Value::IntList(o)) => b.extend_from_slice(o),
+        (Value::LongList(b), Value::LongList(o)) => b.extend_from_slice(o),
+        (Value::FloatList(b), Value::FloatList(o)) => b.extend_from_slice(o),
+        (Value::DoubleList(b), Value::DoubleList(o)) => b.extend_from_slice(o),
+        (Value::ByteArrayList(b), Value::ByteArrayList(o)) => b.extend_from_slice(o),
+        (Value::StrList(b), Value::StrList(o)) => b.extend_from_slice(o),
+        (Value::ListList(b), Value::ListList(o)) => b.extend_from_slice(o),
+        (Value::CompoundList(b), Value::CompoundList(o)) => b.extend_from_slice(o),
+        (Value::IntArrayList(b), Value::IntArrayList(o)) => b.extend_from_slice(o),
+        (Value::LongArrayList(b), Value::LongArrayList(o)) => b.extend_from_slice(o),
+        (b, o) => *b = o.clone(),
+    }
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 pub type Compound = BTreeMap<String, Value>;
 
 #[derive(Debug, Clone)]
 pub enum Error {
+    /// Index and actual length.
+    IndexOutOfRange(usize, usize),
+    InvalidLength(usize, usize),
     InvalidType(&'static str, &'static str),
+    InvalidUtf8(core::str::Utf8Error),
+    /// Element `index` of a list has tag type `found`, unlike the first element of `declared` type.
+    ListTypeMismatch { declared: TagType, index: usize, found: TagType },
+    /// NaN or infinite float, which can't be written as SNBT.
+    NonFinite(f64),
     NotFound(String),
+    /// Value and the type it doesn't fit in.
+    Overflow(i64, &'static str),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Error::IndexOutOfRange(index, len) => write!(f, "index out of range: {} but length {}", index, len),
+            Error::InvalidLength(to_be, actual) => write!(f, "invalid length: wanted {} but actual {}", to_be, actual),
             Error::InvalidType(to_be, actual) => write!(f, "invalid type: wanted '{}' but actual '{}'", to_be, actual),
+            Error::InvalidUtf8(e) => write!(f, "invalid UTF-8: {}", e),
+            Error::ListTypeMismatch { declared, index, found } => {
+                write!(f, "list element {} is {:?} but the first is {:?}", index, found, declared)
+            }
+            Error::NonFinite(x) => write!(f, "non-finite float: {}", x),
             Error::NotFound(name) => write!(f, "field not found '{}'", name),
+            Error::Overflow(x, to_be) => write!(f, "overflow: {} doesn't fit in '{}'", x, to_be),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
\ No newline at end of file
@@ -0,0 +1,91 @@
+//! Java modified UTF-8, used by Java Edition NBT strings.
+//!
+//! It differs from UTF-8 in two ways: NUL is written as `C0 80`,
+//! and supplementary characters are written as a surrogate pair of two 3-byte sequences.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Decode modified UTF-8. `None` if malformed.
+///
+/// Plain UTF-8 is also accepted, since many non-Java writers produce it.
+#[cfg(feature = "std")]
+pub(crate) fn decode(bs: &[u8]) -> Option<String> {
+    decode_cow(bs).map(Cow::into_owned)
+}
+
+/// Decode modified UTF-8, borrowing `bs` if it is also valid UTF-8.
+pub(crate) fn decode_cow(bs: &[u8]) -> Option<Cow<'_, str>> {
+    if let Ok(s) = core::str::from_utf8(bs) {
+        return Some(Cow::Borrowed(s));
+    }
+
+    let mut units = Vec::with_capacity(bs.len());
+    let mut i = 0;
+    while i < bs.len() {
+        let b = bs[i];
+        let (width, init) = match b {
+            0x00..=0x7F => (1, u32::from(b)),
+            0xC0..=0xDF => (2, u32::from(b & 0x1F)),
+            0xE0..=0xEF => (3, u32::from(b & 0x0F)),
+            0xF0..=0xF7 => (4, u32::from(b & 0x07)),
+            _ => return None,
+        };
+        let tail = bs.get(i + 1..i + width)?;
+        let mut code = init;
+        for &t in tail {
+            if t & 0xC0 != 0x80 {
+                return None;
+            }
+            code = (code << 6) | u32::from(t & 0x3F);
+        }
+        i += width;
+
+        // Plain UTF-8 4-byte sequence mixed with modified UTF-8 parts.
+        if width == 4 {
+            let c = core::char::from_u32(code)?;
+            let mut buf = [0u16; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buf));
+        } else {
+            units.push(code as u16);
+        }
+    }
+
+    String::from_utf16(&units).ok().map(Cow::Owned)
+}
+
+/// Encode string as modified UTF-8.
+#[cfg(feature = "std")]
+pub(crate) fn encode(s: &str) -> Cow<'_, [u8]> {
+    if !s.chars().any(|c| c == '\0' || c > '\u{FFFF}') {
+        return Cow::Borrowed(s.as_bytes());
+    }
+
+    let mut bs = Vec::with_capacity(s.len() + 2);
+    for c in s.chars() {
+        if c == '\0' {
+            bs.extend_from_slice(&[0xC0, 0x80]);
+        } else if c > '\u{FFFF}' {
+            let mut buf = [0u16; 2];
+            for &unit in c.encode_utf16(&mut buf).iter() {
+                bs.push(0xE0 | (unit >> 12) as u8);
+                bs.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                bs.push(0x80 | (unit & 0x3F) as u8);
+            }
+        } else {
+            let mut buf = [0u8; 4];
+            bs.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    Cow::Owned(bs)
+}
+
+/// Byte length of `s` encoded by `encode`.
+pub(crate) fn encoded_len(s: &str) -> usize {
+    s.len() + s.chars().map(|c| match c {
+        '\0' => 1,
+        c if c > '\u{FFFF}' => 2,
+        _ => 0,
+    }).sum::<usize>()
+}
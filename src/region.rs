@@ -0,0 +1,315 @@
+use std::fmt;
+use std::io;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::decode::{ParseError, Parser, Result};
+use crate::encode::{EncodeError, Encoder};
+use crate::*;
+
+/// Size of a sector in region files.
+pub const SECTOR_SIZE: u64 = 4096;
+
+/// Number of chunks along one side of a region.
+pub const REGION_WIDTH: usize = 32;
+
+/// Reader of region (`.mca`) files.
+///
+/// A region file starts with a table of 1024 chunk locations and a table of 1024 timestamps,
+/// followed by compressed chunk NBT aligned to 4 KiB sectors.
+#[derive(Debug)]
+pub struct RegionReader<R> {
+    r: R,
+    locations: Vec<u32>,
+    timestamps: Vec<u32>,
+}
+
+impl<R: Read + Seek> RegionReader<R> {
+    /// Create reader, reading the header tables.
+    pub fn new(mut r: R) -> Result<RegionReader<R>> {
+        r.seek(SeekFrom::Start(0))?;
+        let locations = read_table(&mut r)?;
+        let timestamps = read_table(&mut r)?;
+
+        Ok(RegionReader { r, locations, timestamps })
+    }
+
+    /// Read the chunk at (`x`, `z`) in the region, or `None` if it is not generated.
+    ///
+    /// Returns the root compound without its name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `z` is not less than 32.
+    pub fn chunk(&mut self, x: u8, z: u8) -> Result<Option<Value>> {
+        let (start, length, compression) = match self.seek_chunk(x, z)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        let data = (&mut self.r).take(u64::from(length) - 1);
+        let (_, value) = match compression {
+            1 => Parser::new(GzDecoder::new(data)).parse_named()?,
+            2 => Parser::new(ZlibDecoder::new(data)).parse_named()?,
+            3 => Parser::new(data).parse_named()?,
+            b => {
+                return Err(ParseError::Parse {
+                    offset: start + 4,
+                    found: b,
+                    context: "chunk compression type",
+                })
+            }
+        };
+
+        Ok(Some(value))
+    }
+
+    /// Read the chunk at (`x`, `z`) without decompressing it, or `None` if it is not generated.
+    ///
+    /// Returns the compression type byte (1 gzip, 2 zlib, 3 uncompressed) and the compressed data,
+    /// as taken by `RegionWriter::set_raw_chunk`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `z` is not less than 32.
+    pub fn raw_chunk(&mut self, x: u8, z: u8) -> Result<Option<(u8, Vec<u8>)>> {
+        let (_, length, compression) = match self.seek_chunk(x, z)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        let mut data = Vec::new();
+        (&mut self.r).take(u64::from(length) - 1).read_to_end(&mut data)?;
+        if data.len() as u64 != u64::from(length) - 1 {
+            return Err(ParseError::UnexpectedEndOfInput);
+        }
+
+        Ok(Some((compression, data)))
+    }
+
+    /// Last modification time of the chunk at (`x`, `z`) in seconds since the epoch, or 0 if it is not generated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `z` is not less than 32.
+    pub fn timestamp(&self, x: u8, z: u8) -> u32 {
+        self.timestamps[index(x, z)]
+    }
+
+    /// Unwrap the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
+    /// Seek to the data of the chunk at (`x`, `z`) and return its offset, length, and compression type.
+    fn seek_chunk(&mut self, x: u8, z: u8) -> Result<Option<(u64, u32, u8)>> {
+        let location = self.locations[index(x, z)];
+        if location == 0 {
+            return Ok(None);
+        }
+
+        let start = u64::from(location >> 8) * SECTOR_SIZE;
+        self.r.seek(SeekFrom::Start(start))?;
+
+        let mut header = [0u8; 5];
+        self.r.read_exact(&mut header)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        if length == 0 {
+            return Err(ParseError::Parse {
+                offset: start,
+                found: 0,
+                context: "chunk length",
+            });
+        }
+
+        Ok(Some((start, length, header[4])))
+    }
+}
+
+/// Maximum number of sectors of one chunk, as the location table stores it in a byte.
+const MAX_CHUNK_SECTORS: u64 = 255;
+
+/// Writer of region (`.mca`) files.
+///
+/// Chunks are kept in memory until `finish`, which writes the whole file.
+/// Chunks are laid out one after another from sector 2, so a chunk can grow or shrink freely
+/// and a region copied by `from_reader` is compacted.
+#[derive(Debug)]
+pub struct RegionWriter<W> {
+    w: W,
+    chunks: Vec<Option<RawChunk>>,
+}
+
+#[derive(Debug)]
+struct RawChunk {
+    compression: u8,
+    data: Vec<u8>,
+    timestamp: u32,
+}
+
+impl<W: Write + Seek> RegionWriter<W> {
+    /// Create writer of an empty region.
+    pub fn new(w: W) -> RegionWriter<W> {
+        RegionWriter {
+            w,
+            chunks: (0..REGION_WIDTH * REGION_WIDTH).map(|_| None).collect(),
+        }
+    }
+
+    /// Create writer holding all chunks of `reader`, with their compressed data and timestamps unchanged.
+    pub fn from_reader<R: Read + Seek>(reader: &mut RegionReader<R>, w: W) -> Result<RegionWriter<W>> {
+        let mut writer = RegionWriter::new(w);
+        for z in 0..REGION_WIDTH as u8 {
+            for x in 0..REGION_WIDTH as u8 {
+                if let Some((compression, data)) = reader.raw_chunk(x, z)? {
+                    writer.chunks[index(x, z)] = Some(RawChunk {
+                        compression,
+                        data,
+                        timestamp: reader.timestamp(x, z),
+                    });
+                }
+            }
+        }
+        Ok(writer)
+    }
+
+    /// Set the chunk at (`x`, `z`) to `value` as an unnamed root compound, compressed with zlib like vanilla.
+    ///
+    /// `timestamp` is the modification time in seconds since the epoch.
+    /// Fails with `RegionWriteError::ChunkTooLarge` if the compressed chunk needs more than 255 sectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `z` is not less than 32.
+    pub fn set_chunk(&mut self, x: u8, z: u8, value: &Value, timestamp: u32) -> WriteResult<()> {
+        let mut enc = Encoder::new(ZlibEncoder::new(Vec::new(), Compression::default()));
+        enc.write("", value)?;
+        let data = enc.into_inner().finish()?;
+        self.set_raw_chunk(x, z, 2, data, timestamp)
+    }
+
+    /// Set the chunk at (`x`, `z`) to already compressed `data`, like `RegionReader::raw_chunk` returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `z` is not less than 32.
+    pub fn set_raw_chunk(&mut self, x: u8, z: u8, compression: u8, data: Vec<u8>, timestamp: u32) -> WriteResult<()> {
+        let i = index(x, z);
+        if sector_count(data.len()) > MAX_CHUNK_SECTORS {
+            return Err(RegionWriteError::ChunkTooLarge(data.len()));
+        }
+
+        self.chunks[i] = Some(RawChunk {
+            compression,
+            data,
+            timestamp,
+        });
+        Ok(())
+    }
+
+    /// Remove the chunk at (`x`, `z`), so the game generates it again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `z` is not less than 32.
+    pub fn remove_chunk(&mut self, x: u8, z: u8) {
+        self.chunks[index(x, z)] = None;
+    }
+
+    /// Write the region from the start of the writer and return it.
+    ///
+    /// The output is shorter than the previous content when chunks were removed or compacted,
+    /// so write to an empty file or truncate it afterwards.
+    pub fn finish(mut self) -> WriteResult<W> {
+        let mut locations = Vec::with_capacity(SECTOR_SIZE as usize);
+        let mut timestamps = Vec::with_capacity(SECTOR_SIZE as usize);
+        let mut sector = 2;
+        for chunk in &self.chunks {
+            match chunk {
+                Some(chunk) => {
+                    let count = sector_count(chunk.data.len());
+                    locations.extend_from_slice(&((sector << 8 | count) as u32).to_be_bytes());
+                    timestamps.extend_from_slice(&chunk.timestamp.to_be_bytes());
+                    sector += count;
+                }
+                None => {
+                    locations.extend_from_slice(&[0; 4]);
+                    timestamps.extend_from_slice(&[0; 4]);
+                }
+            }
+        }
+
+        self.w.seek(SeekFrom::Start(0))?;
+        self.w.write_all(&locations)?;
+        self.w.write_all(&timestamps)?;
+        for chunk in self.chunks.iter().flatten() {
+            self.w.write_all(&(chunk.data.len() as u32 + 1).to_be_bytes())?;
+            self.w.write_all(&[chunk.compression])?;
+            self.w.write_all(&chunk.data)?;
+
+            let padding = sector_count(chunk.data.len()) * SECTOR_SIZE - (chunk.data.len() as u64 + 5);
+            self.w.write_all(&vec![0; padding as usize])?;
+        }
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+/// Error of `RegionWriter`.
+#[derive(Debug)]
+pub enum RegionWriteError {
+    /// Compressed chunk of this many bytes does not fit in the 255 sectors a region location can address.
+    ChunkTooLarge(usize),
+    Encode(EncodeError),
+}
+
+impl fmt::Display for RegionWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegionWriteError::ChunkTooLarge(size) => write!(f, "too large chunk: {} bytes", size),
+            RegionWriteError::Encode(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RegionWriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RegionWriteError::Encode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<EncodeError> for RegionWriteError {
+    fn from(e: EncodeError) -> RegionWriteError {
+        RegionWriteError::Encode(e)
+    }
+}
+
+impl From<io::Error> for RegionWriteError {
+    fn from(e: io::Error) -> RegionWriteError {
+        RegionWriteError::Encode(EncodeError::WriteError(e))
+    }
+}
+
+pub type WriteResult<T> = std::result::Result<T, RegionWriteError>;
+
+/// Number of sectors for chunk data of `len` bytes, including the 5 bytes header.
+fn sector_count(len: usize) -> u64 {
+    (len as u64 + 5).div_ceil(SECTOR_SIZE)
+}
+
+fn index(x: u8, z: u8) -> usize {
+    assert!((x as usize) < REGION_WIDTH && (z as usize) < REGION_WIDTH, "chunk ({}, {}) is out of region", x, z);
+    x as usize + z as usize * REGION_WIDTH
+}
+
+fn read_table<R: Read>(r: &mut R) -> io::Result<Vec<u32>> {
+    let mut bs = vec![0u8; SECTOR_SIZE as usize];
+    r.read_exact(&mut bs)?;
+    Ok(bs.chunks(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
@@ -0,0 +1,132 @@
+//! Declarative type check of compounds.
+//!
+//! ```
+//! use raw_nbt::decode::from_snbt;
+//! use raw_nbt::schema::{validate, Schema};
+//! use raw_nbt::TagType;
+//!
+//! let schema = Schema::new()
+//!     .field("Health", TagType::Float)
+//!     .compound("abilities", Schema::new().field("flying", TagType::Byte));
+//!
+//! let player = from_snbt("{Health:20.0f,abilities:{flying:1s}}").unwrap();
+//! let errors = validate(&player, &schema).unwrap_err();
+//! assert_eq!(errors[0].path, "abilities.flying");
+//! ```
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use crate::*;
+
+/// Expected entries of a compound.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    fields: BTreeMap<String, Expected>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expected {
+    Tag(TagType),
+    Compound(Schema),
+}
+
+impl Schema {
+    pub fn new() -> Schema {
+        Schema::default()
+    }
+
+    /// Require entry `name` of type `tag`.
+    pub fn field(mut self, name: impl Into<String>, tag: TagType) -> Schema {
+        self.fields.insert(name.into(), Expected::Tag(tag));
+        self
+    }
+
+    /// Require entry `name` to be a compound matching `schema`.
+    pub fn compound(mut self, name: impl Into<String>, schema: Schema) -> Schema {
+        self.fields.insert(name.into(), Expected::Compound(schema));
+        self
+    }
+}
+
+/// A mismatch found by `validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// Dotted path of the entry. Empty for the root.
+    pub path: String,
+    pub expected: TagType,
+    /// `None` if the entry is missing.
+    pub actual: Option<TagType>,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(f, "'{}': wanted {:?} but actual {:?}", self.path, self.expected, actual),
+            None => write!(f, "'{}': wanted {:?} but missing", self.path, self.expected),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchemaError {}
+
+/// Check `value` against `schema`, collecting all mismatches.
+///
+/// Entries not in the schema are allowed.
+pub fn validate(value: &Value, schema: &Schema) -> core::result::Result<(), Vec<SchemaError>> {
+    let mut errors = Vec::new();
+    validate_in(value, schema, &mut String::new(), &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_in(value: &Value, schema: &Schema, path: &mut String, errors: &mut Vec<SchemaError>) {
+    let compound = match value {
+        Value::Compound(x) => x,
+        _ => {
+            errors.push(SchemaError {
+                path: path.clone(),
+                expected: TagType::Compound,
+                actual: Some(value.tag_type()),
+            });
+            return;
+        }
+    };
+
+    for (name, expected) in &schema.fields {
+        let len = path.len();
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(name);
+
+        let actual = compound.get(name);
+        match expected {
+            Expected::Compound(nested) => match actual {
+                Some(v) => validate_in(v, nested, path, errors),
+                None => errors.push(SchemaError {
+                    path: path.clone(),
+                    expected: TagType::Compound,
+                    actual: None,
+                }),
+            },
+            Expected::Tag(tag) => {
+                let actual = actual.map(Value::tag_type);
+                if actual != Some(*tag) {
+                    errors.push(SchemaError {
+                        path: path.clone(),
+                        expected: *tag,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        path.truncate(len);
+    }
+}
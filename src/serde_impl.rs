@@ -0,0 +1,139 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{Compound, Value};
+
+/// Compounds are serialized as maps, lists and arrays as sequences, and others as their natural types.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Byte(x) => serializer.serialize_i8(*x),
+            Value::Short(x) => serializer.serialize_i16(*x),
+            Value::Int(x) => serializer.serialize_i32(*x),
+            Value::Long(x) => serializer.serialize_i64(*x),
+            Value::Float(x) => serializer.serialize_f32(*x),
+            Value::Double(x) => serializer.serialize_f64(*x),
+            Value::ByteArray(xs) => serializer.collect_seq(xs),
+            Value::Str(x) => serializer.serialize_str(x),
+            Value::Compound(x) => serializer.collect_map(x),
+            Value::IntArray(xs) => serializer.collect_seq(xs),
+            Value::LongArray(xs) => serializer.collect_seq(xs),
+            Value::EndList => serializer.collect_seq(&[] as &[Value]),
+            Value::EmptyByteList => serializer.collect_seq(&[] as &[Value]),
+            Value::ByteList(xs) => serializer.collect_seq(xs),
+            Value::ShortList(xs) => serializer.collect_seq(xs),
+            Value::IntList(xs) => serializer.collect_seq(xs),
+            Value::LongList(xs) => serializer.collect_seq(xs),
+            Value::FloatList(xs) => serializer.collect_seq(xs),
+            Value::DoubleList(xs) => serializer.collect_seq(xs),
+            Value::ByteArrayList(xs) => serializer.collect_seq(xs),
+            Value::StrList(xs) => serializer.collect_seq(xs),
+            Value::ListList(xs) => serializer.collect_seq(xs),
+            Value::CompoundList(xs) => serializer.collect_seq(xs),
+            Value::IntArrayList(xs) => serializer.collect_seq(xs),
+            Value::LongArrayList(xs) => serializer.collect_seq(xs),
+        }
+    }
+}
+
+/// Maps to NBT types as follows, since formats like JSON lose NBT's distinction between widths:
+///
+/// * bool: `Byte` (0 or 1)
+/// * integer: `Int` if it fits in `i32`, otherwise `Long`
+/// * floating point number: `Double`
+/// * string: `Str`
+/// * map: `Compound`
+/// * sequence: list of the element type, or `EndList` if empty.
+///   When integers and floating point numbers are mixed, all elements are widened to `Long` or `Double`.
+///   Other mixed element types are rejected.
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an NBT value")
+    }
+
+    fn visit_bool<E: de::Error>(self, x: bool) -> Result<Value, E> {
+        Ok(Value::Byte(x as i8))
+    }
+
+    fn visit_i64<E: de::Error>(self, x: i64) -> Result<Value, E> {
+        if x >= i64::from(i32::MIN) && x <= i64::from(i32::MAX) {
+            Ok(Value::Int(x as i32))
+        } else {
+            Ok(Value::Long(x))
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, x: u64) -> Result<Value, E> {
+        if x > i64::MAX as u64 {
+            return Err(E::invalid_value(de::Unexpected::Unsigned(x), &"an integer in range of i64"));
+        }
+        self.visit_i64(x as i64)
+    }
+
+    fn visit_f64<E: de::Error>(self, x: f64) -> Result<Value, E> {
+        Ok(Value::Double(x))
+    }
+
+    fn visit_str<E: de::Error>(self, x: &str) -> Result<Value, E> {
+        Ok(Value::Str(x.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, x: String) -> Result<Value, E> {
+        Ok(Value::Str(x))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut elements: Vec<Value> = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(x) = seq.next_element()? {
+            elements.push(x);
+        }
+
+        widen_numbers(&mut elements);
+        Value::make_list(elements).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut compound = Compound::new();
+        while let Some((k, v)) = map.next_entry()? {
+            compound.insert(k, v);
+        }
+        Ok(Value::Compound(compound))
+    }
+}
+
+/// Widen mixed number elements to `Long` or `Double`.
+fn widen_numbers(elements: &mut [Value]) {
+    if !elements.iter().all(|v| matches!(v, Value::Int(_) | Value::Long(_) | Value::Double(_))) {
+        return;
+    }
+
+    if elements.iter().any(|v| matches!(v, Value::Double(_))) {
+        for v in elements.iter_mut() {
+            *v = match *v {
+                Value::Int(x) => Value::Double(f64::from(x)),
+                Value::Long(x) => Value::Double(x as f64),
+                ref x => x.clone(),
+            };
+        }
+    } else if elements.iter().any(|v| matches!(v, Value::Long(_))) {
+        for v in elements.iter_mut() {
+            if let Value::Int(x) = *v {
+                *v = Value::Long(i64::from(x));
+            }
+        }
+    }
+}
@@ -0,0 +1,576 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::fmt::Write;
+
+use crate::decode::{self, ParseError};
+use crate::*;
+
+/// Options of SNBT output for `Value::write_snbt_with` and `Value::to_snbt`.
+///
+/// By default, output is compact and compound keys are in the order of `Compound`, that is sorted by bytes.
+/// The binary encoder always writes in that order.
+/// Since `Compound` does not remember insertion order, the original order of a file can't be restored.
+///
+/// Non-finite floats are an error by default. `Display` can't fail, so it uses `NonFinite::Raw` instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnbtOptions {
+    pretty: bool,
+    key_order: Option<fn(&str, &str) -> Ordering>,
+    non_finite: NonFinite,
+    precision: Option<usize>,
+}
+
+/// How to write NaN and infinite floats, which SNBT can't represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinite {
+    /// Fail. `Value::to_snbt` returns `Error::NonFinite`.
+    #[default]
+    Error,
+    /// Write `0.0` with the type suffix.
+    Zero,
+    /// Write string `"NaN"`, `"Infinity"`, or `"-Infinity"`. In a list, this mixes element types.
+    Str,
+    /// Write like `NaNf` or `infd`, which is not valid SNBT.
+    Raw,
+}
+
+impl SnbtOptions {
+    pub fn new() -> SnbtOptions {
+        SnbtOptions::default()
+    }
+
+    /// Indent nested compounds and lists, like `{:#}`.
+    pub fn pretty(mut self, pretty: bool) -> SnbtOptions {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Order compound keys by `cmp` instead of the default.
+    pub fn key_order(mut self, cmp: fn(&str, &str) -> Ordering) -> SnbtOptions {
+        self.key_order = Some(cmp);
+        self
+    }
+
+    /// Set how to write NaN and infinite floats.
+    pub fn non_finite(mut self, non_finite: NonFinite) -> SnbtOptions {
+        self.non_finite = non_finite;
+        self
+    }
+
+    /// Write floats and doubles with `digits` digits after the decimal point, like `{:.N}`.
+    ///
+    /// By default, the shortest representation that parses back to the same value is written.
+    pub fn precision(mut self, digits: usize) -> SnbtOptions {
+        self.precision = Some(digits);
+        self
+    }
+}
+
+/// SNBT (stringified NBT) writer.
+pub(crate) struct SnbtWriter<'a, W> {
+    w: &'a mut W,
+    pretty: bool,
+    key_order: Option<fn(&str, &str) -> Ordering>,
+    non_finite: NonFinite,
+    precision: Option<usize>,
+    indent: usize,
+    max_len: usize,
+}
+
+impl<'a, W: Write> SnbtWriter<'a, W> {
+    pub(crate) fn new(w: &'a mut W, pretty: bool) -> SnbtWriter<'a, W> {
+        SnbtWriter::with_options(w, SnbtOptions::new().pretty(pretty).non_finite(NonFinite::Raw))
+    }
+
+    pub(crate) fn with_options(w: &'a mut W, options: SnbtOptions) -> SnbtWriter<'a, W> {
+        SnbtWriter {
+            w,
+            pretty: options.pretty,
+            key_order: options.key_order,
+            non_finite: options.non_finite,
+            precision: options.precision,
+            indent: 0,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set float precision, as taken from `fmt::Formatter::precision`.
+    pub(crate) fn precision(mut self, precision: Option<usize>) -> SnbtWriter<'a, W> {
+        self.precision = precision;
+        self
+    }
+
+    /// Abbreviate lists and arrays longer than `max_len`. The output is no longer valid SNBT.
+    pub(crate) fn max_len(mut self, max_len: usize) -> SnbtWriter<'a, W> {
+        self.max_len = max_len;
+        self
+    }
+
+    pub(crate) fn write_value(&mut self, value: &Value) -> fmt::Result {
+        if let Some(noun) = element_noun(value) {
+            let len = value.len().unwrap_or(0);
+            if len > self.max_len {
+                return write!(self.w, "[... {} {} ...]", len, noun);
+            }
+        }
+
+        match value {
+            Value::Byte(x) => write!(self.w, "{}b", x),
+            Value::Short(x) => write!(self.w, "{}s", x),
+            Value::Int(x) => write!(self.w, "{}", x),
+            Value::Long(x) => write!(self.w, "{}L", x),
+            Value::Float(x) => write_float(self.w, *x, 'f', self.non_finite, self.precision),
+            Value::Double(x) => write_float(self.w, *x, 'd', self.non_finite, self.precision),
+            Value::ByteArray(xs) => self.write_inline("B;", xs, |w, x| write!(w, "{}b", x)),
+            Value::Str(x) => write_quoted(self.w, x),
+            Value::Compound(x) => self.write_compound(x),
+            Value::IntArray(xs) => self.write_inline("I;", xs, |w, x| write!(w, "{}", x)),
+            Value::LongArray(xs) => self.write_inline("L;", xs, |w, x| write!(w, "{}L", x)),
+            Value::EndList => self.w.write_str("[]"),
+            Value::EmptyByteList => self.w.write_str("[]"),
+            Value::ByteList(xs) => self.write_inline("", xs, |w, x| write!(w, "{}b", x)),
+            Value::ShortList(xs) => self.write_inline("", xs, |w, x| write!(w, "{}s", x)),
+            Value::IntList(xs) => self.write_inline("", xs, |w, x| write!(w, "{}", x)),
+            Value::LongList(xs) => self.write_inline("", xs, |w, x| write!(w, "{}L", x)),
+            Value::FloatList(xs) => {
+                let (non_finite, precision) = (self.non_finite, self.precision);
+                self.write_inline("", xs, |w, x| write_float(w, *x, 'f', non_finite, precision))
+            }
+            Value::DoubleList(xs) => {
+                let (non_finite, precision) = (self.non_finite, self.precision);
+                self.write_inline("", xs, |w, x| write_float(w, *x, 'd', non_finite, precision))
+            }
+            Value::ByteArrayList(xs) => {
+                self.write_block(xs, |s, x| s.write_inline("B;", x, |w, x| write!(w, "{}b", x)))
+            }
+            Value::StrList(xs) => self.write_inline("", xs, |w, x| write_quoted(w, x)),
+            Value::ListList(xs) => self.write_block(xs, |s, x| s.write_value(x)),
+            Value::CompoundList(xs) => self.write_block(xs, |s, x| s.write_compound(x)),
+            Value::IntArrayList(xs) => {
+                self.write_block(xs, |s, x| s.write_inline("I;", x, |w, x| write!(w, "{}", x)))
+            }
+            Value::LongArrayList(xs) => {
+                self.write_block(xs, |s, x| s.write_inline("L;", x, |w, x| write!(w, "{}L", x)))
+            }
+        }
+    }
+
+    fn write_compound(&mut self, x: &Compound) -> fmt::Result {
+        if x.is_empty() {
+            return self.w.write_str("{}");
+        }
+
+        let mut entries: Vec<(&String, &Value)> = x.iter().collect();
+        if let Some(cmp) = self.key_order {
+            entries.sort_by(|a, b| cmp(a.0, b.0));
+        }
+
+        self.w.write_char('{')?;
+        self.indent += 1;
+        for (i, (name, value)) in entries.into_iter().enumerate() {
+            self.write_separator(i)?;
+            write_key(self.w, name)?;
+            self.w.write_str(if self.pretty { ": " } else { ":" })?;
+            self.write_value(value)?;
+        }
+        self.indent -= 1;
+        self.write_newline()?;
+        self.w.write_char('}')
+    }
+
+    /// Write list of primitives in one line.
+    fn write_inline<T, F>(&mut self, prefix: &str, xs: &[T], mut f: F) -> fmt::Result
+    where
+        F: FnMut(&mut W, &T) -> fmt::Result,
+    {
+        self.w.write_char('[')?;
+        self.w.write_str(prefix)?;
+        for (i, x) in xs.iter().enumerate() {
+            if i > 0 {
+                self.w.write_str(if self.pretty { ", " } else { "," })?;
+            }
+            f(self.w, x)?;
+        }
+        self.w.write_char(']')
+    }
+
+    /// Write list of nested values, one element per line in pretty mode.
+    fn write_block<T, F>(&mut self, xs: &[T], mut f: F) -> fmt::Result
+    where
+        F: FnMut(&mut Self, &T) -> fmt::Result,
+    {
+        if xs.is_empty() {
+            return self.w.write_str("[]");
+        }
+
+        self.w.write_char('[')?;
+        self.indent += 1;
+        for (i, x) in xs.iter().enumerate() {
+            self.write_separator(i)?;
+            f(self, x)?;
+        }
+        self.indent -= 1;
+        self.write_newline()?;
+        self.w.write_char(']')
+    }
+
+    fn write_separator(&mut self, i: usize) -> fmt::Result {
+        if i > 0 {
+            self.w.write_char(',')?;
+        }
+        self.write_newline()
+    }
+
+    fn write_newline(&mut self) -> fmt::Result {
+        if self.pretty {
+            self.w.write_char('\n')?;
+            for _ in 0..self.indent {
+                self.w.write_str("    ")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn element_noun(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::ByteArray(_) | Value::ByteList(_) => Some("bytes"),
+        Value::IntArray(_) | Value::IntList(_) => Some("ints"),
+        Value::LongArray(_) | Value::LongList(_) => Some("longs"),
+        Value::ShortList(_) => Some("shorts"),
+        Value::FloatList(_) => Some("floats"),
+        Value::DoubleList(_) => Some("doubles"),
+        Value::ByteArrayList(_) => Some("byte arrays"),
+        Value::StrList(_) => Some("strs"),
+        Value::ListList(_) => Some("lists"),
+        Value::CompoundList(_) => Some("compounds"),
+        Value::IntArrayList(_) => Some("int arrays"),
+        Value::LongArrayList(_) => Some("long arrays"),
+        _ => None,
+    }
+}
+
+fn write_float<W, T>(w: &mut W, x: T, suffix: char, non_finite: NonFinite, precision: Option<usize>) -> fmt::Result
+where
+    W: Write,
+    T: fmt::Debug + fmt::Display + Copy + Into<f64>,
+{
+    let f: f64 = x.into();
+    if f.is_finite() || non_finite == NonFinite::Raw {
+        return match precision {
+            Some(digits) if f.is_finite() => write!(w, "{:.*}{}", digits, x, suffix),
+            _ => write!(w, "{:?}{}", x, suffix),
+        };
+    }
+
+    match non_finite {
+        NonFinite::Zero => write!(w, "0.0{}", suffix),
+        NonFinite::Str if f.is_nan() => write_quoted(w, "NaN"),
+        NonFinite::Str if f > 0.0 => write_quoted(w, "Infinity"),
+        NonFinite::Str => write_quoted(w, "-Infinity"),
+        _ => Err(fmt::Error),
+    }
+}
+
+fn write_key<W: Write>(w: &mut W, name: &str) -> fmt::Result {
+    if !name.is_empty() && name.chars().all(is_bare_char) {
+        w.write_str(name)
+    } else {
+        write_quoted(w, name)
+    }
+}
+
+fn write_quoted<W: Write>(w: &mut W, s: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            w.write_char('\\')?;
+        }
+        w.write_char(c)?;
+    }
+    w.write_char('"')
+}
+
+fn is_bare_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '+'
+}
+
+/// SNBT (stringified NBT) parser.
+pub(crate) struct SnbtParser<'a> {
+    s: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> SnbtParser<'a> {
+    pub(crate) fn new(s: &'a str) -> SnbtParser<'a> {
+        SnbtParser { s, pos: 0, depth: 0 }
+    }
+
+    pub(crate) fn parse(&mut self) -> decode::Result<Value> {
+        let value = self.parse_value()?;
+        self.skip_ws();
+        if self.pos < self.s.len() {
+            return Err(self.error_at(self.pos, "trailing character in SNBT"));
+        }
+        Ok(value)
+    }
+
+    //// parse ////
+
+    fn parse_value(&mut self) -> decode::Result<Value> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'{') => {
+                self.enter()?;
+                let compound = self.parse_compound();
+                self.depth -= 1;
+                Ok(Value::Compound(compound?))
+            }
+            Some(b'[') => {
+                self.enter()?;
+                let list = self.parse_list();
+                self.depth -= 1;
+                list
+            }
+            Some(b'"') | Some(b'\'') => Ok(Value::Str(self.parse_quoted()?)),
+            Some(_) => {
+                let token = self.parse_bare();
+                if token.is_empty() {
+                    return Err(self.error_at(self.pos, "character in SNBT value"));
+                }
+                Ok(scalar(token))
+            }
+            None => Err(ParseError::UnexpectedEndOfInput),
+        }
+    }
+
+    fn parse_compound(&mut self) -> decode::Result<Compound> {
+        self.pos += 1;
+        let mut compound = Compound::new();
+
+        self.skip_ws();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(compound);
+        }
+
+        loop {
+            let key = self.parse_key()?;
+            self.skip_ws();
+            if self.peek() != Some(b':') {
+                return Err(self.error_at(self.pos, "character after SNBT key"));
+            }
+            self.pos += 1;
+
+            let value = self.parse_value()?;
+            compound.insert(key, value);
+
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(compound);
+                }
+                _ => return Err(self.error_at(self.pos, "character in SNBT compound")),
+            }
+        }
+    }
+
+    fn parse_list(&mut self) -> decode::Result<Value> {
+        self.pos += 1;
+
+        let bs = self.s.as_bytes();
+        if bs.get(self.pos + 1) == Some(&b';') {
+            if let Some(&kind) = bs.get(self.pos).filter(|b| [b'B', b'I', b'L'].contains(b)) {
+                self.pos += 2;
+                return self.parse_array(kind);
+            }
+        }
+
+        let elements = self.parse_elements()?;
+        let (starts, values): (Vec<usize>, Vec<Value>) = elements.into_iter().unzip();
+        Value::make_list(values).map_err(|e| match e {
+            Error::ListTypeMismatch { declared, index, .. } => ParseError::ListTypeMismatch {
+                declared,
+                index,
+                offset: starts[index] as u64,
+            },
+            _ => unreachable!(),
+        })
+    }
+
+    fn parse_array(&mut self, kind: u8) -> decode::Result<Value> {
+        let elements = self.parse_elements()?;
+        let context = "element type in SNBT array";
+
+        match kind {
+            b'B' => elements
+                .into_iter()
+                .map(|(start, v)| v.byte().map_err(|_| self.error_at(start, context)))
+                .collect::<decode::Result<Vec<i8>>>()
+                .map(Value::ByteArray),
+            b'I' => elements
+                .into_iter()
+                .map(|(start, v)| v.int().map_err(|_| self.error_at(start, context)))
+                .collect::<decode::Result<Vec<i32>>>()
+                .map(Value::IntArray),
+            _ => elements
+                .into_iter()
+                .map(|(start, v)| v.long().map_err(|_| self.error_at(start, context)))
+                .collect::<decode::Result<Vec<i64>>>()
+                .map(Value::LongArray),
+        }
+    }
+
+    /// Parse comma separated values until `]`, with their start positions.
+    fn parse_elements(&mut self) -> decode::Result<Vec<(usize, Value)>> {
+        let mut elements = Vec::new();
+
+        self.skip_ws();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(elements);
+        }
+
+        loop {
+            self.skip_ws();
+            let start = self.pos;
+            elements.push((start, self.parse_value()?));
+
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(elements);
+                }
+                _ => return Err(self.error_at(self.pos, "character in SNBT list")),
+            }
+        }
+    }
+
+    fn parse_key(&mut self) -> decode::Result<String> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'"') | Some(b'\'') => self.parse_quoted(),
+            _ => {
+                let token = self.parse_bare();
+                if token.is_empty() {
+                    return Err(self.error_at(self.pos, "character in SNBT key"));
+                }
+                Ok(token.to_string())
+            }
+        }
+    }
+
+    fn parse_quoted(&mut self) -> decode::Result<String> {
+        let quote = self.s.as_bytes()[self.pos] as char;
+        self.pos += 1;
+
+        let mut out = String::new();
+        let mut chars = self.s[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                self.pos += i + 1;
+                return Ok(out);
+            }
+            if c == '\\' {
+                match chars.next() {
+                    Some((_, e)) if e == '\\' || e == '"' || e == '\'' => out.push(e),
+                    Some(_) => return Err(self.error_at(self.pos + i, "escape in SNBT string")),
+                    None => break,
+                }
+            } else {
+                out.push(c);
+            }
+        }
+
+        Err(ParseError::UnexpectedEndOfInput)
+    }
+
+    fn parse_bare(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| is_bare_char(b as char)) {
+            self.pos += 1;
+        }
+        &self.s[start..self.pos]
+    }
+
+    //// util ////
+
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn enter(&mut self) -> decode::Result<()> {
+        if self.depth >= decode::DEFAULT_MAX_DEPTH {
+            return Err(ParseError::DepthLimitExceeded(decode::DEFAULT_MAX_DEPTH));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn error_at(&self, pos: usize, context: &'static str) -> ParseError {
+        match self.s.as_bytes().get(pos) {
+            Some(&found) => ParseError::Parse {
+                offset: pos as u64,
+                found,
+                context,
+            },
+            None => ParseError::UnexpectedEndOfInput,
+        }
+    }
+}
+
+/// Convert a bare token into number, boolean, or unquoted string.
+fn scalar(token: &str) -> Value {
+    match token {
+        "true" => return Value::Byte(1),
+        "false" => return Value::Byte(0),
+        _ => {}
+    }
+
+    let (body, suffix) = token.split_at(token.len() - 1);
+    let parsed = match suffix {
+        "b" | "B" if is_integer(body) => body.parse().ok().map(Value::Byte),
+        "s" | "S" if is_integer(body) => body.parse().ok().map(Value::Short),
+        "l" | "L" if is_integer(body) => body.parse().ok().map(Value::Long),
+        "f" | "F" if is_float(body) => body.parse().ok().map(Value::Float),
+        "d" | "D" if is_float(body) => body.parse().ok().map(Value::Double),
+        _ if is_integer(token) => token.parse().ok().map(Value::Int),
+        _ if is_float(token) => token.parse().ok().map(Value::Double),
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| Value::Str(token.to_string()))
+}
+
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_float(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+
+    let mut parts = mantissa.splitn(2, '.');
+    let int_part = parts.next().unwrap_or("");
+    let frac_part = parts.next().unwrap_or("");
+    let valid_mantissa = !(int_part.is_empty() && frac_part.is_empty())
+        && int_part.bytes().all(|b| b.is_ascii_digit())
+        && frac_part.bytes().all(|b| b.is_ascii_digit());
+
+    valid_mantissa && exponent.is_none_or(is_integer)
+}
@@ -0,0 +1,121 @@
+//! Reader of the vanilla structure block format (`.nbt` files in `generated/<namespace>/structures`).
+//!
+//! ```
+//! use raw_nbt::decode::from_snbt;
+//! use raw_nbt::structure::parse_structure;
+//!
+//! let value = from_snbt(
+//!     r#"{DataVersion:3465,size:[1,2,1],palette:[{Name:"minecraft:stone"}],blocks:[{state:0,pos:[0,1,0]}],entities:[]}"#,
+//! )
+//! .unwrap();
+//! let structure = parse_structure(&value).unwrap();
+//! assert_eq!(structure.size, [1, 2, 1]);
+//! assert_eq!(structure.state_of(&structure.blocks[0]).name, "minecraft:stone");
+//! ```
+
+use alloc::collections::BTreeMap;
+
+use crate::*;
+
+/// A structure read by `parse_structure`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    /// `DataVersion` of the game that saved the structure.
+    pub data_version: Option<i32>,
+    /// `size` as `[x, y, z]`.
+    pub size: [i32; 3],
+    /// `palette`, or the first of `palettes` for structures with variants, like shipwrecks.
+    pub palette: Vec<BlockState>,
+    /// `blocks`. Positions not listed are left as they are when the structure is placed.
+    pub blocks: Vec<StructureBlock>,
+}
+
+/// A palette entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockState {
+    /// `Name`, like `minecraft:oak_stairs`.
+    pub name: String,
+    /// `Properties`, like `facing` to `north`. Empty if missing.
+    pub properties: BTreeMap<String, String>,
+}
+
+/// A block in a structure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureBlock {
+    /// `state`, an index into `Structure::palette`.
+    pub state: usize,
+    /// `pos` as `[x, y, z]` relative to the structure origin.
+    pub pos: [i32; 3],
+    /// `nbt`, block entity data like chest contents.
+    pub nbt: Option<Compound>,
+}
+
+impl Structure {
+    /// Palette entry of `block`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block` is not from this structure, so its state is out of the palette.
+    pub fn state_of(&self, block: &StructureBlock) -> &BlockState {
+        &self.palette[block.state]
+    }
+}
+
+/// Read a structure from its root compound.
+///
+/// Fails with `Error::NotFound` if `size`, `palette` (or `palettes`), or `blocks` is missing,
+/// `Error::InvalidLength` if a position is not 3 ints,
+/// and `Error::IndexOutOfRange` or `Error::Overflow` if a state is out of the palette or negative.
+/// `entities` and unknown entries are ignored.
+pub fn parse_structure(value: &Value) -> Result<Structure> {
+    let root = value.compound()?;
+
+    let data_version = root.get("DataVersion").map(Value::int).transpose()?;
+    let size = parse_pos(root.get_value("size")?)?;
+
+    let palette = match root.get("palette") {
+        Some(palette) => palette.compound_list()?,
+        None => match root.get_value("palettes")?.list_list()?.first() {
+            Some(first) => Cow::Owned(first.compound_list()?.into_owned()),
+            None => Cow::Owned(Vec::new()),
+        },
+    };
+    let palette = palette.iter().map(parse_block_state).collect::<Result<Vec<_>>>()?;
+
+    let blocks = root.get_compound_list("blocks")?;
+    let blocks = blocks.iter().map(|block| parse_block(block, palette.len())).collect::<Result<Vec<_>>>()?;
+
+    Ok(Structure { data_version, size, palette, blocks })
+}
+
+fn parse_block_state(x: &Compound) -> Result<BlockState> {
+    let name = x.get_str("Name")?.to_string();
+    let mut properties = BTreeMap::new();
+    if let Some(props) = x.get("Properties") {
+        for (k, v) in props.entries()? {
+            properties.insert(k.clone(), v.str()?.to_string());
+        }
+    }
+    Ok(BlockState { name, properties })
+}
+
+fn parse_block(x: &Compound, palette_len: usize) -> Result<StructureBlock> {
+    let state = x.get_int("state")?;
+    let state = usize::try_from(state).map_err(|_| Error::Overflow(i64::from(state), "palette index"))?;
+    if state >= palette_len {
+        return Err(Error::IndexOutOfRange(state, palette_len));
+    }
+
+    Ok(StructureBlock {
+        state,
+        pos: parse_pos(x.get_value("pos")?)?,
+        nbt: x.get("nbt").map(|v| v.compound().cloned()).transpose()?,
+    })
+}
+
+fn parse_pos(value: &Value) -> Result<[i32; 3]> {
+    match value.int_list()?[..] {
+        [x, y, z] => Ok([x, y, z]),
+        ref xs => Err(Error::InvalidLength(3, xs.len())),
+    }
+}
@@ -0,0 +1,58 @@
+/// NBT tag type.
+///
+/// Each variant's discriminant is its tag id in NBT binary, so `TagType::Compound as u8` is 10.
+/// Use `from_id` and `to_id` to convert from and to the id byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TagType {
+    End = 0,
+    Byte = 1,
+    Short = 2,
+    Int = 3,
+    Long = 4,
+    Float = 5,
+    Double = 6,
+    ByteArray = 7,
+    Str = 8,
+    List = 9,
+    Compound = 10,
+    IntArray = 11,
+    LongArray = 12,
+}
+
+impl TagType {
+    /// Tag id in NBT binary.
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Tag id in NBT binary. Same as `id`.
+    pub fn to_id(self) -> u8 {
+        self.id()
+    }
+
+    /// Get tag type of tag id, or `None` for an unknown id.
+    pub fn from_id(id: u8) -> Option<TagType> {
+        match id {
+            0 => Some(TagType::End),
+            1 => Some(TagType::Byte),
+            2 => Some(TagType::Short),
+            3 => Some(TagType::Int),
+            4 => Some(TagType::Long),
+            5 => Some(TagType::Float),
+            6 => Some(TagType::Double),
+            7 => Some(TagType::ByteArray),
+            8 => Some(TagType::Str),
+            9 => Some(TagType::List),
+            10 => Some(TagType::Compound),
+            11 => Some(TagType::IntArray),
+            12 => Some(TagType::LongArray),
+            _ => None,
+        }
+    }
+}
+
+impl From<TagType> for u8 {
+    fn from(tag: TagType) -> u8 {
+        tag.id()
+    }
+}
@@ -0,0 +1,212 @@
+//! Streaming rewrite of NBT binary, without building the whole tree.
+//!
+//! ```
+//! use raw_nbt::decode::from_slice;
+//! use raw_nbt::encode::to_writer;
+//! use raw_nbt::transform::{transform, Action};
+//! use raw_nbt::{CompoundBuilder, TagType, Value};
+//!
+//! let player = CompoundBuilder::new().string("Name", "Steve").int("Score", 3).long("LastSeen", 100).build();
+//! let mut input = Vec::new();
+//! to_writer(&mut input, "", &player).unwrap();
+//!
+//! let mut output = Vec::new();
+//! transform(input.as_slice(), &mut output, |path, _tag: TagType| match path {
+//!     "LastSeen" => Action::Drop,
+//!     "Name" => Action::Rename("name".to_string()),
+//!     "Score" => Action::Modify(Box::new(|v| Value::Int(v.int().unwrap() * 10))),
+//!     _ => Action::Keep,
+//! })
+//! .unwrap();
+//!
+//! let root = from_slice(&output).unwrap();
+//! assert_eq!(root[""].to_string(), r#"{Score:30,name:"Steve"}"#);
+//! ```
+
+use std::fmt;
+use std::io::{Read, Write};
+
+use crate::decode::{ParseError, Parser};
+use crate::encode::{EncodeError, Encoder};
+use crate::{TagType, Value};
+
+/// What `transform` does with a compound entry.
+pub enum Action {
+    /// Copy the entry, deciding its nested entries in turn.
+    Keep,
+    /// Leave the entry out. Its payload is skipped without decoding.
+    Drop,
+    /// Copy the entry under another name. Nested entries keep paths of the original name.
+    Rename(String),
+    /// Decode the entry and write the returned value instead. Nested entries are not decided separately.
+    Modify(Box<dyn FnOnce(Value) -> Value>),
+}
+
+/// Copy root tags from `r` to `w`, letting `f` decide each compound entry by its path and tag type.
+///
+/// Paths are in the syntax of `Value::find_all`, like `Level.Entities[0].id`, starting below the root name.
+/// Root tags themselves are decided with their name as the path.
+/// Only compound entries are decided, so list lengths never change. Only one entry decoded
+/// for `Action::Modify` and one array or scalar are held in memory at a time.
+pub fn transform<R, W, F>(r: R, w: W, mut f: F) -> Result<()>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&str, TagType) -> Action,
+{
+    let mut parser = Parser::new(r);
+    let mut enc = Encoder::new(w);
+
+    while let Some(tag) = parser.read_tag()? {
+        if tag == TagType::End {
+            return Err(ParseError::UnexpectedTag.into());
+        }
+        let name = parser.read_str()?;
+        let mut t = Transformer {
+            parser: &mut parser,
+            enc: &mut enc,
+            f: &mut f,
+            path: String::new(),
+        };
+        t.entry(tag, &name, true)?;
+    }
+
+    enc.into_inner().flush().map_err(EncodeError::from)?;
+    Ok(())
+}
+
+struct Transformer<'a, R, W, F> {
+    parser: &'a mut Parser<R>,
+    enc: &'a mut Encoder<W>,
+    f: &'a mut F,
+    path: String,
+}
+
+impl<R, W, F> Transformer<'_, R, W, F>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&str, TagType) -> Action,
+{
+    fn entry(&mut self, tag: TagType, name: &str, root: bool) -> Result<()> {
+        let len = self.path.len();
+        if !root && !self.path.is_empty() {
+            self.path.push('.');
+        }
+        self.path.push_str(name);
+
+        match (self.f)(&self.path, tag) {
+            Action::Keep => {
+                self.enc.write_tag(tag)?;
+                self.enc.write_str(name)?;
+                self.copy_payload(tag, root)?;
+            }
+            Action::Drop => self.parser.skip_with_tag(tag)?,
+            Action::Rename(new_name) => {
+                self.enc.write_tag(tag)?;
+                self.enc.write_str(&new_name)?;
+                self.copy_payload(tag, root)?;
+            }
+            Action::Modify(g) => {
+                let value = self.parser.parse_value_with_tag(tag)?;
+                self.enc.write(name, &g(value))?;
+            }
+        }
+
+        self.path.truncate(len);
+        Ok(())
+    }
+
+    /// Copy payload of `tag`. Entries of a root compound get paths from their own names.
+    fn copy_payload(&mut self, tag: TagType, root: bool) -> Result<()> {
+        match tag {
+            TagType::Compound => {
+                if root {
+                    self.path.clear();
+                }
+
+                self.parser.enter()?;
+                loop {
+                    match self.parser.read_tag()? {
+                        Some(TagType::End) => break,
+                        Some(tag) => {
+                            let name = self.parser.read_str()?;
+                            self.entry(tag, &name, false)?;
+                        }
+                        None => return Err(ParseError::UnexpectedEndOfInput.into()),
+                    }
+                }
+                self.parser.leave();
+                self.enc.write_tag(TagType::End)?;
+            }
+            TagType::List => {
+                self.parser.enter()?;
+                let element = self.parser.read_tag()?.ok_or(ParseError::UnexpectedEndOfInput)?;
+                let size = self.parser.read_len(TagType::List)?;
+                let size = if element == TagType::End { 0 } else { size };
+                self.enc.write_tag(element)?;
+                self.enc.write_len(size)?;
+
+                for i in 0..size {
+                    match element {
+                        TagType::Compound | TagType::List => {
+                            let len = self.path.len();
+                            self.path.push_str(&format!("[{}]", i));
+                            self.copy_payload(element, false)?;
+                            self.path.truncate(len);
+                        }
+                        _ => {
+                            let value = self.parser.parse_value_with_tag(element)?;
+                            self.enc.write_value(&value)?;
+                        }
+                    }
+                }
+                self.parser.leave();
+            }
+            _ => {
+                let value = self.parser.parse_value_with_tag(tag)?;
+                self.enc.write_value(&value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error of `transform`.
+#[derive(Debug)]
+pub enum TransformError {
+    Parse(ParseError),
+    Encode(EncodeError),
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransformError::Parse(e) => write!(f, "reading input: {}", e),
+            TransformError::Encode(e) => write!(f, "writing output: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransformError::Parse(e) => Some(e),
+            TransformError::Encode(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseError> for TransformError {
+    fn from(e: ParseError) -> TransformError {
+        TransformError::Parse(e)
+    }
+}
+
+impl From<EncodeError> for TransformError {
+    fn from(e: EncodeError) -> TransformError {
+        TransformError::Encode(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, TransformError>;
@@ -0,0 +1,372 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::*;
+
+/// NBT value borrowing strings and byte arrays from the input buffer.
+///
+/// Created by `decode::SliceParser::parse_named_ref`.
+/// Strings are borrowed unless they needed modified UTF-8 conversion.
+/// Multi-byte numbers are big-endian in the input, so arrays and lists of them are owned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    // basic types
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [i8]),
+    Str(Cow<'a, str>),
+    Compound(CompoundRef<'a>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    // list
+    EndList,
+    EmptyByteList,
+    ByteList(&'a [i8]),
+    ShortList(Vec<i16>),
+    IntList(Vec<i32>),
+    LongList(Vec<i64>),
+    FloatList(Vec<f32>),
+    DoubleList(Vec<f64>),
+    ByteArrayList(Vec<&'a [i8]>),
+    StrList(Vec<Cow<'a, str>>),
+    ListList(Vec<ValueRef<'a>>),
+    CompoundList(Vec<CompoundRef<'a>>),
+    IntArrayList(Vec<Vec<i32>>),
+    LongArrayList(Vec<Vec<i64>>),
+}
+
+pub type CompoundRef<'a> = BTreeMap<Cow<'a, str>, ValueRef<'a>>;
+
+impl<'a> ValueRef<'a> {
+    pub fn byte(&self) -> Result<i8> {
+        match self {
+            ValueRef::Byte(x) => Ok(*x),
+            _ => Err(Error::InvalidType("byte", self.val_type())),
+        }
+    }
+
+    pub fn short(&self) -> Result<i16> {
+        match self {
+            ValueRef::Short(x) => Ok(*x),
+            _ => Err(Error::InvalidType("short", self.val_type())),
+        }
+    }
+
+    pub fn int(&self) -> Result<i32> {
+        match self {
+            ValueRef::Int(x) => Ok(*x),
+            _ => Err(Error::InvalidType("int", self.val_type())),
+        }
+    }
+
+    pub fn long(&self) -> Result<i64> {
+        match self {
+            ValueRef::Long(x) => Ok(*x),
+            _ => Err(Error::InvalidType("long", self.val_type())),
+        }
+    }
+
+    pub fn float(&self) -> Result<f32> {
+        match self {
+            ValueRef::Float(x) => Ok(*x),
+            _ => Err(Error::InvalidType("float", self.val_type())),
+        }
+    }
+
+    pub fn double(&self) -> Result<f64> {
+        match self {
+            ValueRef::Double(x) => Ok(*x),
+            _ => Err(Error::InvalidType("double", self.val_type())),
+        }
+    }
+
+    pub fn byte_array(&self) -> Result<&'a [i8]> {
+        match self {
+            ValueRef::ByteArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte array", self.val_type())),
+        }
+    }
+
+    /// Get string. It borrows `self` even if the string is borrowed from the input;
+    /// match `ValueRef::Str(Cow::Borrowed(s))` to keep it after dropping the value.
+    pub fn str(&self) -> Result<&str> {
+        match self {
+            ValueRef::Str(x) => Ok(x),
+            _ => Err(Error::InvalidType("str", self.val_type())),
+        }
+    }
+
+    pub fn compound(&self) -> Result<&CompoundRef<'a>> {
+        match self {
+            ValueRef::Compound(x) => Ok(x),
+            _ => Err(Error::InvalidType("compound", self.val_type())),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<&ValueRef<'a>> {
+        self.compound()?.get(name).ok_or_else(|| Error::NotFound(name.to_string()))
+    }
+
+    pub fn int_array(&self) -> Result<&[i32]> {
+        match self {
+            ValueRef::IntArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("int array", self.val_type())),
+        }
+    }
+
+    pub fn long_array(&self) -> Result<&[i64]> {
+        match self {
+            ValueRef::LongArray(x) => Ok(x),
+            _ => Err(Error::InvalidType("long array", self.val_type())),
+        }
+    }
+
+    pub fn byte_list(&self) -> Result<&'a [i8]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::ByteList(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte list", self.val_type())),
+        }
+    }
+
+    pub fn short_list(&self) -> Result<&[i16]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::ShortList(x) => Ok(x),
+            _ => Err(Error::InvalidType("short list", self.val_type())),
+        }
+    }
+
+    pub fn int_list(&self) -> Result<&[i32]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::IntList(x) => Ok(x),
+            _ => Err(Error::InvalidType("int list", self.val_type())),
+        }
+    }
+
+    pub fn long_list(&self) -> Result<&[i64]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::LongList(x) => Ok(x),
+            _ => Err(Error::InvalidType("long list", self.val_type())),
+        }
+    }
+
+    pub fn float_list(&self) -> Result<&[f32]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::FloatList(x) => Ok(x),
+            _ => Err(Error::InvalidType("float list", self.val_type())),
+        }
+    }
+
+    pub fn double_list(&self) -> Result<&[f64]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::DoubleList(x) => Ok(x),
+            _ => Err(Error::InvalidType("double list", self.val_type())),
+        }
+    }
+
+    pub fn byte_array_list(&self) -> Result<&[&'a [i8]]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::ByteArrayList(x) => Ok(x),
+            _ => Err(Error::InvalidType("byte array list", self.val_type())),
+        }
+    }
+
+    pub fn str_list(&self) -> Result<&[Cow<'a, str>]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::StrList(x) => Ok(x),
+            _ => Err(Error::InvalidType("str list", self.val_type())),
+        }
+    }
+
+    pub fn list_list(&self) -> Result<&[ValueRef<'a>]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::ListList(x) => Ok(x),
+            _ => Err(Error::InvalidType("list list", self.val_type())),
+        }
+    }
+
+    pub fn compound_list(&self) -> Result<&[CompoundRef<'a>]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::CompoundList(x) => Ok(x),
+            _ => Err(Error::InvalidType("compound list", self.val_type())),
+        }
+    }
+
+    pub fn int_array_list(&self) -> Result<&[Vec<i32>]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::IntArrayList(x) => Ok(x),
+            _ => Err(Error::InvalidType("int array list", self.val_type())),
+        }
+    }
+
+    pub fn long_array_list(&self) -> Result<&[Vec<i64>]> {
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Ok(&[]),
+            ValueRef::LongArrayList(x) => Ok(x),
+            _ => Err(Error::InvalidType("long array list", self.val_type())),
+        }
+    }
+
+    /// Get list element at `index`, for any list variant, like `Value::list_get`.
+    ///
+    /// `ListList` elements are borrowed. Other elements are returned owned,
+    /// but strings and byte arrays in them stay borrowed from the input.
+    pub fn list_get(&self, index: usize) -> Result<Cow<'_, ValueRef<'a>>> {
+        fn get<'b, 'a, T: Clone>(xs: &[T], index: usize, f: fn(T) -> ValueRef<'a>) -> Result<Cow<'b, ValueRef<'a>>> {
+            match xs.get(index) {
+                Some(x) => Ok(Cow::Owned(f(x.clone()))),
+                None => Err(Error::IndexOutOfRange(index, xs.len())),
+            }
+        }
+
+        match self {
+            ValueRef::EndList | ValueRef::EmptyByteList => Err(Error::IndexOutOfRange(index, 0)),
+            ValueRef::ByteList(xs) => get(xs, index, ValueRef::Byte),
+            ValueRef::ShortList(xs) => get(xs, index, ValueRef::Short),
+            ValueRef::IntList(xs) => get(xs, index, ValueRef::Int),
+            ValueRef::LongList(xs) => get(xs, index, ValueRef::Long),
+            ValueRef::FloatList(xs) => get(xs, index, ValueRef::Float),
+            ValueRef::DoubleList(xs) => get(xs, index, ValueRef::Double),
+            ValueRef::ByteArrayList(xs) => get(xs, index, ValueRef::ByteArray),
+            ValueRef::StrList(xs) => get(xs, index, ValueRef::Str),
+            ValueRef::ListList(xs) => xs.get(index).map(Cow::Borrowed).ok_or(Error::IndexOutOfRange(index, xs.len())),
+            ValueRef::CompoundList(xs) => get(xs, index, ValueRef::Compound),
+            ValueRef::IntArrayList(xs) => get(xs, index, ValueRef::IntArray),
+            ValueRef::LongArrayList(xs) => get(xs, index, ValueRef::LongArray),
+            _ => Err(Error::InvalidType("list", self.val_type())),
+        }
+    }
+
+    /// Get element count of list, array, or compound. `None` for other types.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            ValueRef::ByteArray(x) => Some(x.len()),
+            ValueRef::Compound(x) => Some(x.len()),
+            ValueRef::IntArray(x) => Some(x.len()),
+            ValueRef::LongArray(x) => Some(x.len()),
+            ValueRef::EndList => Some(0),
+            ValueRef::EmptyByteList => Some(0),
+            ValueRef::ByteList(x) => Some(x.len()),
+            ValueRef::ShortList(x) => Some(x.len()),
+            ValueRef::IntList(x) => Some(x.len()),
+            ValueRef::LongList(x) => Some(x.len()),
+            ValueRef::FloatList(x) => Some(x.len()),
+            ValueRef::DoubleList(x) => Some(x.len()),
+            ValueRef::ByteArrayList(x) => Some(x.len()),
+            ValueRef::StrList(x) => Some(x.len()),
+            ValueRef::ListList(x) => Some(x.len()),
+            ValueRef::CompoundList(x) => Some(x.len()),
+            ValueRef::IntArrayList(x) => Some(x.len()),
+            ValueRef::LongArrayList(x) => Some(x.len()),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|n| n == 0)
+    }
+
+    /// Get tag type. All list variants are `TagType::List`.
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            ValueRef::Byte(_) => TagType::Byte,
+            ValueRef::Short(_) => TagType::Short,
+            ValueRef::Int(_) => TagType::Int,
+            ValueRef::Long(_) => TagType::Long,
+            ValueRef::Float(_) => TagType::Float,
+            ValueRef::Double(_) => TagType::Double,
+            ValueRef::ByteArray(_) => TagType::ByteArray,
+            ValueRef::Str(_) => TagType::Str,
+            ValueRef::Compound(_) => TagType::Compound,
+            ValueRef::IntArray(_) => TagType::IntArray,
+            ValueRef::LongArray(_) => TagType::LongArray,
+            _ => TagType::List,
+        }
+    }
+
+    /// Copy into an owned `Value`.
+    pub fn to_owned(&self) -> Value {
+        self.clone().into_owned()
+    }
+
+    /// Convert into an owned `Value`, reusing owned buffers.
+    pub fn into_owned(self) -> Value {
+        match self {
+            ValueRef::Byte(x) => Value::Byte(x),
+            ValueRef::Short(x) => Value::Short(x),
+            ValueRef::Int(x) => Value::Int(x),
+            ValueRef::Long(x) => Value::Long(x),
+            ValueRef::Float(x) => Value::Float(x),
+            ValueRef::Double(x) => Value::Double(x),
+            ValueRef::ByteArray(xs) => Value::ByteArray(xs.to_vec()),
+            ValueRef::Str(x) => Value::Str(x.into_owned()),
+            ValueRef::Compound(x) => Value::Compound(into_owned_compound(x)),
+            ValueRef::IntArray(xs) => Value::IntArray(xs),
+            ValueRef::LongArray(xs) => Value::LongArray(xs),
+            ValueRef::EndList => Value::EndList,
+            ValueRef::EmptyByteList => Value::EmptyByteList,
+            ValueRef::ByteList(xs) => Value::ByteList(xs.to_vec()),
+            ValueRef::ShortList(xs) => Value::ShortList(xs),
+            ValueRef::IntList(xs) => Value::IntList(xs),
+            ValueRef::LongList(xs) => Value::LongList(xs),
+            ValueRef::FloatList(xs) => Value::FloatList(xs),
+            ValueRef::DoubleList(xs) => Value::DoubleList(xs),
+            ValueRef::ByteArrayList(xs) => Value::ByteArrayList(xs.into_iter().map(<[i8]>::to_vec).collect()),
+            ValueRef::StrList(xs) => Value::StrList(xs.into_iter().map(Cow::into_owned).collect()),
+            ValueRef::ListList(xs) => Value::ListList(xs.into_iter().map(ValueRef::into_owned).collect()),
+            ValueRef::CompoundList(xs) => Value::CompoundList(xs.into_iter().map(into_owned_compound).collect()),
+            ValueRef::IntArrayList(xs) => Value::IntArrayList(xs),
+            ValueRef::LongArrayList(xs) => Value::LongArrayList(xs),
+        }
+    }
+
+    fn val_type(&self) -> &'static str {
+        match self {
+            ValueRef::Byte(_) => "byte",
+            ValueRef::Short(_) => "short",
+            ValueRef::Int(_) => "int",
+            ValueRef::Long(_) => "long",
+            ValueRef::Float(_) => "float",
+            ValueRef::Double(_) => "double",
+            ValueRef::ByteArray(_) => "byte array",
+            ValueRef::Str(_) => "str",
+            ValueRef::Compound(_) => "compound",
+            ValueRef::IntArray(_) => "int array",
+            ValueRef::LongArray(_) => "long array",
+            ValueRef::EndList => "end list",
+            ValueRef::EmptyByteList => "byte list (empty)",
+            ValueRef::ByteList(_) => "byte list (non-empty)",
+            ValueRef::ShortList(_) => "short list",
+            ValueRef::IntList(_) => "int list",
+            ValueRef::LongList(_) => "long list",
+            ValueRef::FloatList(_) => "float list",
+            ValueRef::DoubleList(_) => "double list",
+            ValueRef::ByteArrayList(_) => "byte array list",
+            ValueRef::StrList(_) => "str list",
+            ValueRef::ListList(_) => "list list",
+            ValueRef::CompoundList(_) => "compound list",
+            ValueRef::IntArrayList(_) => "int array list",
+            ValueRef::LongArrayList(_) => "long array list",
+        }
+    }
+}
+
+fn into_owned_compound(x: CompoundRef<'_>) -> Compound {
+    x.into_iter().map(|(k, v)| (k.into_owned(), v.into_owned())).collect()
+}
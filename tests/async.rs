@@ -0,0 +1,89 @@
+#![cfg(feature = "async")]
+
+use std::fs::File;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use raw_nbt::decode::{parse, AsyncParser, ParseError};
+use raw_nbt::encode::Encoder;
+use raw_nbt::{Compound, TagType, Value};
+
+#[tokio::test]
+async fn parse_level_async() {
+    let mut bytes = Vec::new();
+    GzDecoder::new(File::open("./testdata/level.dat").unwrap()).read_to_end(&mut bytes).unwrap();
+
+    let mut parser = AsyncParser::new(bytes.as_slice());
+    assert_eq!(parser.parse().await.unwrap(), parse(bytes.as_slice()).unwrap());
+    assert_eq!(parser.offset(), bytes.len() as u64);
+}
+
+#[tokio::test]
+async fn parse_stream_of_messages() {
+    let mut c = Compound::new();
+    c.insert("id".to_string(), Value::Int(7));
+    c.insert("pos".to_string(), Value::DoubleList(vec![1.0, 2.0]));
+    c.insert("tags".to_string(), Value::ListList(vec![Value::StrList(vec!["a".to_string()]), Value::EndList]));
+
+    let mut enc = Encoder::new(Vec::new());
+    enc.write("first", &Value::Compound(c.clone())).unwrap();
+    enc.write("second", &Value::Str("hello".to_string())).unwrap();
+    let bytes = enc.into_inner();
+
+    let mut parser = AsyncParser::new(bytes.as_slice()).allow_any_root(true);
+    assert_eq!(parser.parse_named().await.unwrap(), ("first".to_string(), Value::Compound(c)));
+    assert_eq!(parser.parse_named().await.unwrap(), ("second".to_string(), Value::Str("hello".to_string())));
+    match parser.parse_named().await {
+        Err(ParseError::UnexpectedEndOfInput) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[tokio::test]
+async fn parse_async_errors() {
+    // int array declaring 2^30 elements, followed by nothing
+    let bytes = [11, 0, 1, b'a', 0x40, 0, 0, 0];
+    match AsyncParser::new(&bytes[..]).allow_any_root(true).parse_named().await {
+        Err(ParseError::UnexpectedEndOfInput) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match AsyncParser::new(&bytes[..]).allow_any_root(true).max_elements(16).parse_named().await {
+        Err(ParseError::LengthLimitExceeded { declared, limit: 16 }) => assert_eq!(declared, 1 << 30),
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let nested = [10, 0, 0, 10, 0, 1, b'a', 10, 0, 1, b'b', 0, 0, 0];
+    match AsyncParser::new(&nested[..]).max_depth(2).parse_named().await {
+        Err(ParseError::DepthLimitExceeded(2)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let unknown = [10, 0, 0, 13, 0, 0];
+    match AsyncParser::new(&unknown[..]).parse_named().await {
+        Err(e) => assert_eq!(e.unknown_tag(), Some(13)),
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let invalid_str = [1, 0, 0, 5, 8, 0, 1, b'a', 0, 1, 0xff, 0];
+    match AsyncParser::new(&invalid_str[..]).allow_any_root(true).parse().await {
+        Err(ParseError::InvalidString { offset }) => assert_eq!(offset, 10),
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[tokio::test]
+async fn parse_async_root_not_compound() {
+    let bytes = [3, 0, 1, b'a', 0, 0, 0, 1];
+    match AsyncParser::new(&bytes[..]).parse_named().await {
+        Err(ParseError::RootNotCompound(TagType::Int)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match AsyncParser::new(&bytes[..]).parse().await {
+        Err(ParseError::RootNotCompound(TagType::Int)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let mut parser = AsyncParser::new(&bytes[..]).allow_any_root(true);
+    assert_eq!(parser.parse_named().await.unwrap(), ("a".to_string(), Value::Int(1)));
+}
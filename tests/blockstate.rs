@@ -0,0 +1,90 @@
+use raw_nbt::blockstate::{pack_block_states, unpack_block_states, unpack_with_layout, Layout};
+use raw_nbt::Error;
+
+/// Example `BlockStates` from the Minecraft Wiki chunk format page: 24 entries of 5 bits in the 1.16+ layout.
+const PADDED: [i64; 2] = [0x0020_8631_4841_8841, 0x0101_8A72_60F6_8C87];
+
+/// The same entries in the pre-1.16 layout, where the 13th entry spans the two longs.
+const SPANNING: [i64; 2] = [0x7020_8631_4841_8841, 0x0010_18A7_260F_68C8];
+
+const ENTRIES: [u16; 24] = [1, 2, 2, 3, 4, 4, 5, 6, 6, 4, 8, 0, 7, 4, 3, 13, 15, 16, 9, 14, 10, 12, 0, 2];
+
+#[test]
+fn unpack_both_layouts() {
+    let entries: Vec<u16> = (0..4096).map(|i| (i * 7 % 23) as u16).collect();
+
+    for &layout in &[Layout::Spanning, Layout::Padded] {
+        let data = pack_block_states(&entries, 5, layout).unwrap();
+        assert_eq!(unpack_with_layout(&data, 5, 4096, layout).unwrap(), entries);
+        assert_eq!(unpack_block_states(&data, 5, 4096).unwrap(), entries);
+    }
+
+    assert_eq!(unpack_with_layout(&PADDED, 5, 24, Layout::Padded).unwrap(), ENTRIES);
+    assert_eq!(unpack_with_layout(&SPANNING, 5, 24, Layout::Spanning).unwrap(), ENTRIES);
+
+    assert_eq!(Layout::Spanning.long_count(5, 4096), 320);
+    assert_eq!(Layout::Padded.long_count(5, 4096), 342);
+}
+
+#[test]
+fn unpack_full_width() {
+    let entries: Vec<u16> = vec![0, 0xffff, 0x1234, 0x8000, 7];
+    let data = [0x8000_1234_FFFF_0000u64 as i64, 7];
+    assert_eq!(unpack_block_states(&data, 16, 5).unwrap(), entries);
+    assert_eq!(pack_block_states(&entries, 16, Layout::Padded).unwrap(), data);
+}
+
+#[test]
+fn unpack_wrong_length() {
+    match unpack_block_states(&[0; 100], 5, 4096) {
+        Err(Error::InvalidLength(342, 100)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match unpack_with_layout(&[0; 319], 5, 4096, Layout::Spanning) {
+        Err(Error::InvalidLength(320, 319)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn unpack_invalid_bits() {
+    for &bits in &[0, 17] {
+        match unpack_block_states(&[0; 4], bits, 4) {
+            Err(Error::Overflow(b, "bits per entry")) => assert_eq!(b, bits as i64),
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert!(matches!(unpack_with_layout(&[0; 4], bits, 4, Layout::Padded), Err(Error::Overflow(_, _))));
+    }
+}
+
+#[test]
+fn pack_both_layouts() {
+    let entries: Vec<u16> = (0..4096).map(|i| (i * 7 % 23) as u16).collect();
+
+    assert_eq!(pack_block_states(&ENTRIES, 5, Layout::Padded).unwrap(), PADDED);
+    assert_eq!(pack_block_states(&ENTRIES, 5, Layout::Spanning).unwrap(), SPANNING);
+
+    for &layout in &[Layout::Spanning, Layout::Padded] {
+        for &bits in &[5, 13, 16] {
+            let data = pack_block_states(&entries, bits, layout).unwrap();
+            assert_eq!(unpack_with_layout(&data, bits, entries.len(), layout).unwrap(), entries);
+        }
+    }
+    assert!(pack_block_states(&[], 4, Layout::Padded).unwrap().is_empty());
+}
+
+#[test]
+fn pack_overflow() {
+    match pack_block_states(&[1, 2, 32], 5, Layout::Spanning) {
+        Err(Error::Overflow(32, _)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    assert!(pack_block_states(&[31], 5, Layout::Spanning).is_ok());
+
+    for &bits in &[0, 17] {
+        match pack_block_states(&[1], bits, Layout::Padded) {
+            Err(Error::Overflow(b, "bits per entry")) => assert_eq!(b, bits as i64),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+}
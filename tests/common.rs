@@ -0,0 +1,31 @@
+use raw_nbt::common::{parse_items, Item};
+use raw_nbt::decode::from_snbt;
+use raw_nbt::{Compound, Error, Value};
+
+#[test]
+fn parse_inventory() {
+    let player = from_snbt(
+        r#"{Inventory:[{Slot:0b,id:"minecraft:stone",Count:64b},{Slot:9b,id:"minecraft:diamond_sword",Count:1b,tag:{Damage:3},ForgeCaps:{x:1}},{id:"minecraft:apple",count:5,components:{}}],EnderItems:[]}"#,
+    )
+    .unwrap();
+
+    let items = parse_items(&player["Inventory"]).unwrap();
+    assert_eq!(
+        items[0],
+        Item {
+            id: "minecraft:stone".to_string(),
+            count: 64,
+            slot: Some(0),
+            tag: None,
+            raw: Compound::new(),
+        }
+    );
+    assert_eq!(items[1].tag.as_ref().unwrap()["Damage"], Value::Int(3));
+    assert_eq!(items[1].raw["ForgeCaps"], from_snbt("{x:1}").unwrap());
+    assert_eq!((items[2].count, items[2].slot), (5, None));
+    assert!(items[2].raw.contains_key("components"));
+
+    assert!(parse_items(&player["EnderItems"]).unwrap().is_empty());
+    assert!(matches!(parse_items(&from_snbt("[{Count:1b}]").unwrap()), Err(Error::NotFound(_))));
+    assert!(matches!(parse_items(&from_snbt(r#"[{id:"a",Count:1}]"#).unwrap()), Err(Error::InvalidType(_, _))));
+}
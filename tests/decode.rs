@@ -0,0 +1,575 @@
+use std::error::Error;
+use std::io;
+use std::io::Read;
+
+use raw_nbt::decode::{from_slice, from_slice_named, ParseError, Parser, Visitor};
+use raw_nbt::{Compound, TagType, Value};
+
+#[test]
+fn parse_named_root() {
+    let bytes: &[u8] = &[10, 0, 4, b'r', b'o', b'o', b't', 1, 0, 1, b'x', 5, 0];
+    let (name, value) = Parser::new(bytes).parse_named().unwrap();
+
+    let mut expected = Compound::new();
+    expected.insert("x".to_string(), Value::Byte(5));
+    assert_eq!(name, "root");
+    assert_eq!(value, Value::Compound(expected));
+}
+
+#[test]
+fn parse_named_empty_root_name() {
+    let bytes: &[u8] = &[10, 0, 0, 0];
+    let parsed = Parser::new(bytes).parse_named().unwrap();
+    assert_eq!(parsed, (String::new(), Value::Compound(Compound::new())));
+}
+
+#[test]
+fn truncated_input() {
+    let bytes: &[u8] = &[10, 0, 0, 3, 0, 1, b'x', 0, 0];
+    match Parser::new(bytes).parse_named() {
+        Err(ParseError::UnexpectedEndOfInput) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn read_error_is_wrapped() {
+    struct Broken;
+
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken pipe"))
+        }
+    }
+
+    let err = Parser::new(Broken).parse_named().unwrap_err();
+    match err {
+        ParseError::ReadError(ref cause) => assert_eq!(cause.to_string(), "broken pipe"),
+        ref e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(err.source().is_some());
+}
+
+#[test]
+fn unknown_tag_offset() {
+    let bytes: &[u8] = &[10, 0, 0, 1, 0, 1, b'x', 5, 13];
+    match Parser::new(bytes).parse_named() {
+        Err(ParseError::Parse { offset, found, context }) => {
+            assert_eq!((offset, found, context), (8, 13, "tag id"));
+        }
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn unknown_tag_id() {
+    let bytes: &[u8] = &[10, 0, 0, 1, 0, 1, b'x', 5, 13];
+    let err = Parser::new(bytes).parse_named().unwrap_err();
+    assert_eq!(err.unknown_tag(), Some(13));
+    assert_eq!(ParseError::UnexpectedEndOfInput.unknown_tag(), None);
+
+    let negative: &[u8] = &[10, 0, 0, 11, 0, 1, b'a', 0xFF, 0xFF, 0xFF, 0xFF, 0];
+    assert_eq!(Parser::new(negative).parse_named().unwrap_err().unknown_tag(), None);
+}
+
+#[test]
+fn negative_length_offset() {
+    let bytes: &[u8] = &[10, 0, 0, 11, 0, 1, b'a', 0xFF, 0xFF, 0xFF, 0xFF, 0];
+    match Parser::new(bytes).parse_named() {
+        Err(ParseError::Parse { offset, found, context }) => {
+            assert_eq!((offset, found, context), (7, 11, "length of tag"));
+        }
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn depth_limit() {
+    // Root compound containing "l": a list of lists nested `n` deep.
+    fn nested_lists(n: usize) -> Vec<u8> {
+        let mut bytes = vec![10, 0, 0, 9, 0, 1, b'l'];
+        for _ in 1..n {
+            bytes.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        bytes
+    }
+
+    assert!(Parser::new(nested_lists(511).as_slice()).parse_named().is_ok());
+    match Parser::new(nested_lists(512).as_slice()).parse_named() {
+        Err(ParseError::DepthLimitExceeded(512)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Parser::with_max_depth(nested_lists(3).as_slice(), 3).parse_named() {
+        Err(ParseError::DepthLimitExceeded(3)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn length_limit() {
+    let bytes: &[u8] = &[10, 0, 0, 7, 0, 1, b'a', 0x7F, 0xFF, 0xFF, 0xFF];
+    match Parser::with_max_elements(bytes, 1024).parse_named() {
+        Err(ParseError::LengthLimitExceeded { declared, limit }) => {
+            assert_eq!((declared, limit), (0x7FFF_FFFF, 1024));
+        }
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn total_size_limit() {
+    // Compound of 16 long arrays with 1000 elements each, 128000 bytes in total.
+    let mut bytes = vec![10, 0, 0];
+    for i in 0..16u8 {
+        bytes.extend_from_slice(&[12, 0, 1, b'a' + i, 0, 0, 0x03, 0xE8]);
+        bytes.extend_from_slice(&[0; 8000]);
+    }
+    bytes.push(0);
+
+    let mut parser = Parser::with_max_total_bytes(bytes.as_slice(), 100_000).max_elements(1000);
+    match parser.parse_named() {
+        Err(ParseError::SizeLimitExceeded(100_000)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    // The first 12 arrays fit in the limit, and the 13th fails right after its length.
+    assert_eq!(parser.offset(), 3 + 12 * 8008 + 8);
+
+    let (_, value) = Parser::with_max_total_bytes(bytes.as_slice(), 200_000).parse_named().unwrap();
+    assert_eq!(value.len(), Some(16));
+}
+
+#[test]
+fn parse_little_endian() {
+    let bytes: &[u8] = &[
+        10, 1, 0, b'r', // root
+        3, 1, 0, b'i', 0x78, 0x56, 0x34, 0x12, // int
+        5, 1, 0, b'f', 0x00, 0x00, 0x80, 0x3F, // float
+        11, 1, 0, b'a', 2, 0, 0, 0, 1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, // int array
+        0,
+    ];
+    let (name, value) = Parser::new_le(bytes).parse_named().unwrap();
+
+    let mut expected = Compound::new();
+    expected.insert("i".to_string(), Value::Int(0x1234_5678));
+    expected.insert("f".to_string(), Value::Float(1.0));
+    expected.insert("a".to_string(), Value::IntArray(vec![1, -1]));
+    assert_eq!(name, "r");
+    assert_eq!(value, Value::Compound(expected));
+}
+
+#[test]
+fn parse_varint() {
+    let bytes: &[u8] = &[
+        10, 0, // root
+        3, 1, b'i', 0x01, // int -1
+        4, 1, b'l', 0xD8, 0x04, // long 300
+        8, 1, b's', 2, b'a', b'b', // str
+        11, 1, b'a', 0x04, 0x02, 0x03, // int array [1, -2]
+        0,
+    ];
+    let (_, value) = Parser::new_var(bytes).parse_named().unwrap();
+
+    let mut expected = Compound::new();
+    expected.insert("i".to_string(), Value::Int(-1));
+    expected.insert("l".to_string(), Value::Long(300));
+    expected.insert("s".to_string(), Value::Str("ab".to_string()));
+    expected.insert("a".to_string(), Value::IntArray(vec![1, -2]));
+    assert_eq!(value, Value::Compound(expected));
+}
+
+#[test]
+fn parse_varint_out_of_range() {
+    // u32::MAX fits in 5 bytes, but the 5th byte of an int must not set bits above 0x0F.
+    let max: &[u8] = &[10, 0, 3, 1, b'i', 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0];
+    assert_eq!(Parser::new_var(max).parse_named().unwrap().1["i"], Value::Int(i32::MIN));
+
+    let over: &[u8] = &[10, 0, 3, 1, b'i', 0xFF, 0xFF, 0xFF, 0xFF, 0x1F, 0];
+    match Parser::new_var(over).parse_named() {
+        Err(ParseError::Parse { offset, found, context }) => assert_eq!((offset, found, context), (5, 0x1F, "varint")),
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let too_long: &[u8] = &[10, 0, 3, 1, b'i', 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0];
+    assert!(matches!(Parser::new_var(too_long).parse_named(), Err(ParseError::Parse { context: "varint", .. })));
+
+    let long_over: &[u8] = &[10, 0, 4, 1, b'l', 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02, 0];
+    assert!(matches!(Parser::new_var(long_over).parse_named(), Err(ParseError::Parse { context: "varint", .. })));
+}
+
+#[test]
+fn parse_slice() {
+    let bytes: &[u8] = &[10, 0, 1, b'r', 1, 0, 1, b'x', 5, 0];
+
+    let mut root = Compound::new();
+    root.insert("x".to_string(), Value::Byte(5));
+    assert_eq!(from_slice_named(bytes).unwrap(), ("r".to_string(), Value::Compound(root.clone())));
+
+    let mut wrapped = Compound::new();
+    wrapped.insert("r".to_string(), Value::Compound(root));
+    assert_eq!(from_slice(bytes).unwrap(), Value::Compound(wrapped));
+}
+
+#[test]
+fn parse_with_visitor() {
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl Visitor for Log {
+        fn key(&mut self, name: &str) {
+            self.0.push(format!("key {}", name));
+        }
+
+        fn begin_compound(&mut self) {
+            self.0.push("{".to_string());
+        }
+
+        fn end_compound(&mut self) {
+            self.0.push("}".to_string());
+        }
+
+        fn begin_list(&mut self, element: TagType, len: usize) {
+            self.0.push(format!("[{:?};{}", element, len));
+        }
+
+        fn end_list(&mut self) {
+            self.0.push("]".to_string());
+        }
+
+        fn value(&mut self, value: Value) {
+            self.0.push(format!("{:?}", value));
+        }
+    }
+
+    let bytes: &[u8] = &[
+        10, 0, 1, b'r', // root
+        9, 0, 1, b'l', 3, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, // int list
+        9, 0, 1, b'e', 0, 0, 0, 0, 0, // empty list
+        0,
+    ];
+    let mut log = Log::default();
+    Parser::new(bytes).parse_with(&mut log).unwrap();
+
+    let expected = ["key r", "{", "key l", "[Int;2", "Int(1)", "Int(2)", "]", "key e", "[End;0", "]", "}"];
+    assert_eq!(log.0, expected);
+}
+
+#[test]
+fn find_skips_other_entries() {
+    let bytes: &[u8] = &[
+        10, 0, 0, // root
+        9, 0, 1, b'l', 10, 0, 0, 0, 1, 11, 0, 1, b'a', 0, 0, 0, 1, 0, 0, 0, 7, 0, // compound list
+        8, 0, 1, b's', 0, 2, b'h', b'i', // str
+        6, 0, 1, b'd', 0x3F, 0xF0, 0, 0, 0, 0, 0, 0, // double
+        3, 0, 1, b'x', 0, 0, 0, 5, // int
+        0,
+    ];
+
+    assert_eq!(Parser::new(bytes).find("d").unwrap(), Some(Value::Double(1.0)));
+    assert_eq!(Parser::new(bytes).find("x").unwrap(), Some(Value::Int(5)));
+    assert_eq!(Parser::new(bytes).find("y").unwrap(), None);
+}
+
+#[test]
+fn find_skips_numeric_arrays_in_bulk() {
+    struct CountingReader<'a>(&'a [u8], usize);
+
+    impl Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.1 += 1;
+            self.0.read(buf)
+        }
+    }
+
+    let mut bytes = vec![10, 0, 0];
+    bytes.extend_from_slice(&[12, 0, 1, b'a', 0, 1, 0, 0]);
+    bytes.extend_from_slice(&[0; 8 << 16]);
+    bytes.extend_from_slice(&[11, 0, 1, b'b', 0, 1, 0, 0]);
+    bytes.extend_from_slice(&[0; 4 << 16]);
+    bytes.extend_from_slice(&[9, 0, 1, b'c', 4, 0, 1, 0, 0]);
+    bytes.extend_from_slice(&[0; 8 << 16]);
+    bytes.extend_from_slice(&[9, 0, 1, b'd', 3, 0, 1, 0, 0]);
+    bytes.extend_from_slice(&[0; 4 << 16]);
+    bytes.extend_from_slice(&[3, 0, 1, b'x', 0, 0, 0, 5, 0]);
+
+    let mut r = CountingReader(&bytes, 0);
+    assert_eq!(Parser::new(&mut r).find("x").unwrap(), Some(Value::Int(5)));
+    assert!(r.1 < 1000, "{} reads", r.1);
+}
+
+#[test]
+fn modified_utf8_string() {
+    let bytes: &[u8] = &[
+        10, 0, 0, 8, 0, 1, b's', 0, 9, b'a', 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80, 0,
+    ];
+    let (_, root) = Parser::new(bytes).parse_named().unwrap();
+    assert_eq!(root["s"], Value::Str("a\0\u{1F600}".to_string()));
+}
+
+#[test]
+fn invalid_string_offset() {
+    let bytes: &[u8] = &[10, 0, 0, 8, 0, 1, b's', 0, 2, b'a', 0xFF, 0];
+    match Parser::new(bytes).parse_named() {
+        Err(ParseError::InvalidString { offset }) => assert_eq!(offset, 9),
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn reset_reuses_parser() {
+    let first: &[u8] = &[10, 0, 1, b'a', 8, 0, 1, b's', 0, 3, b'f', b'o', b'o', 0];
+    let second: &[u8] = &[8, 0, 1, b'b', 0, 1, b'x'];
+
+    let mut parser = Parser::with_max_depth(first, 4).allow_any_root(true);
+    assert_eq!(parser.parse_named().unwrap().0, "a");
+    let rest = parser.reset(second);
+    assert!(rest.is_empty());
+    assert_eq!(parser.offset(), 0);
+    assert_eq!(parser.parse_named().unwrap(), ("b".to_string(), Value::Str("x".to_string())));
+    assert_eq!(parser.offset(), 7);
+}
+
+#[test]
+fn parse_shallow_placeholders() {
+    use raw_nbt::decode::{from_snbt, SHALLOW_LEN_KEY, SHALLOW_TAG_KEY};
+    use raw_nbt::encode::Encoder;
+
+    let value = from_snbt("{a:1,b:{c:{d:2},e:[1,2,3]},f:[{g:1},{h:2,i:3}]}").unwrap();
+    let mut enc = Encoder::new(Vec::new());
+    enc.write("root", &value).unwrap();
+    let bytes = enc.into_inner();
+
+    let placeholder = |tag: TagType, len: i32| {
+        let mut c = Compound::new();
+        c.insert(SHALLOW_TAG_KEY.to_string(), Value::Byte(tag.id() as i8));
+        c.insert(SHALLOW_LEN_KEY.to_string(), Value::Int(len));
+        c
+    };
+
+    let mut parser = Parser::new(bytes.as_slice());
+    let shallow = parser.parse_shallow(2).unwrap();
+    assert_eq!(parser.offset(), bytes.len() as u64);
+    assert_eq!(shallow.path("root.a").unwrap(), &Value::Int(1));
+    assert_eq!(shallow.path("root.b.c").unwrap(), &Value::Compound(placeholder(TagType::Compound, 1)));
+    assert_eq!(shallow.path("root.b.e").unwrap(), &Value::Compound(placeholder(TagType::List, 3)));
+    assert_eq!(
+        shallow.path("root.f").unwrap(),
+        &Value::CompoundList(vec![placeholder(TagType::Compound, 1), placeholder(TagType::Compound, 2)])
+    );
+
+    let top = Parser::new(bytes.as_slice()).parse_shallow(0).unwrap();
+    assert_eq!(top["root"], Value::Compound(placeholder(TagType::Compound, 3)));
+    assert_eq!(Parser::new(bytes.as_slice()).parse_shallow(usize::MAX).unwrap()["root"], value);
+}
+
+#[test]
+fn strict_trailing_data() {
+    let bytes: &[u8] = &[1, 0, 1, b'a', 5, 1, 0, 1, b'b', 6, 0xFF];
+
+    assert_eq!(Parser::new(&bytes[..5]).allow_any_root(true).strict(true).parse_named().unwrap().1, Value::Byte(5));
+    match Parser::new(bytes).allow_any_root(true).strict(true).parse_named() {
+        Err(ParseError::TrailingData(n)) => assert_eq!(n, 6),
+        r => panic!("unexpected result: {:?}", r),
+    }
+    assert!(matches!(Parser::new(bytes).allow_any_root(true).strict(true).parse(), Err(ParseError::TrailingData(6))));
+
+    // Lenient mode keeps reading root tags.
+    assert_eq!(Parser::new(&bytes[..10]).allow_any_root(true).parse().unwrap().len(), Some(2));
+}
+
+#[test]
+fn root_not_compound() {
+    let bytes: &[u8] = &[3, 0, 1, b'a', 0, 0, 0, 1];
+
+    assert!(matches!(Parser::new(bytes).parse(), Err(ParseError::RootNotCompound(TagType::Int))));
+    assert!(matches!(Parser::new(bytes).parse_named(), Err(ParseError::RootNotCompound(TagType::Int))));
+    assert_eq!(Parser::new(bytes).allow_any_root(true).parse().unwrap()["a"], Value::Int(1));
+}
+
+#[test]
+fn root_not_compound_slice() {
+    use raw_nbt::decode::SliceParser;
+
+    let bytes: &[u8] = &[3, 0, 1, b'a', 0, 0, 0, 1];
+
+    assert!(matches!(from_slice(bytes), Err(ParseError::RootNotCompound(TagType::Int))));
+    assert!(matches!(from_slice_named(bytes), Err(ParseError::RootNotCompound(TagType::Int))));
+    assert!(matches!(SliceParser::new(bytes).parse_ref(), Err(ParseError::RootNotCompound(TagType::Int))));
+    assert_eq!(SliceParser::new(bytes).allow_any_root(true).parse().unwrap()["a"], Value::Int(1));
+}
+
+#[test]
+fn parse_network_root() {
+    // Unnamed compound {a:1}, then unnamed string "hi".
+    let bytes: &[u8] = &[10, 3, 0, 1, b'a', 0, 0, 0, 1, 0, 8, 0, 2, b'h', b'i'];
+
+    let mut parser = Parser::new(bytes);
+    assert_eq!(parser.parse_network().unwrap()["a"], Value::Int(1));
+    assert_eq!(parser.offset(), 10);
+    assert!(matches!(parser.parse_network(), Err(ParseError::RootNotCompound(TagType::Str))));
+
+    let mut parser = Parser::new(&bytes[10..]).allow_any_root(true);
+    assert_eq!(parser.parse_network().unwrap(), Value::Str("hi".to_string()));
+    assert!(matches!(parser.parse_network(), Err(ParseError::UnexpectedEndOfInput)));
+    assert!(matches!(Parser::new(bytes).strict(true).parse_network(), Err(ParseError::TrailingData(5))));
+}
+
+#[test]
+fn parse_all_documents() {
+    let bytes: &[u8] = &[10, 0, 0, 3, 0, 1, b'a', 0, 0, 0, 1, 0, 10, 0, 0, 0, 1, 0, 1, b'x', 7];
+
+    let values = Parser::new(bytes).allow_any_root(true).parse_all().unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0]["a"], Value::Int(1));
+    assert_eq!(values[1], Value::Compound(Compound::new()));
+    assert_eq!(values[2], Value::Byte(7));
+
+    assert!(Parser::new(&bytes[..0]).parse_all().unwrap().is_empty());
+    assert!(matches!(Parser::new(&bytes[..8]).parse_all(), Err(ParseError::UnexpectedEndOfInput)));
+}
+
+#[test]
+fn slice_parser_matches_parser() {
+    use raw_nbt::decode::{from_snbt, SliceParser};
+    use raw_nbt::encode::Encoder;
+
+    let value = from_snbt("{a:[B;1b,2b],b:[1s,2s],c:[[1L],[]],d:[{e:\"x\"}],f:[I;3],g:[L;4L],h:[1.5f],i:[]}").unwrap();
+    let mut enc = Encoder::new(Vec::new());
+    enc.write("root", &value).unwrap();
+    let mut bytes = enc.into_inner();
+    bytes.extend_from_slice(&[1, 0, 1, b'z']);
+
+    let mut parser = SliceParser::new(&bytes).allow_any_root(true);
+    assert_eq!(parser.parse_named().unwrap(), ("root".to_string(), value));
+    assert_eq!(parser.remaining(), [1, 0, 1, b'z']);
+    assert_eq!(parser.offset(), bytes.len() as u64 - 4);
+    assert!(matches!(parser.parse_named(), Err(ParseError::UnexpectedEndOfInput)));
+
+    let idcounts = std::fs::read("./testdata/idcounts.dat").unwrap();
+    assert_eq!(from_slice(&idcounts).unwrap(), Parser::new(idcounts.as_slice()).parse().unwrap());
+}
+
+#[test]
+fn slice_parser_errors() {
+    use raw_nbt::decode::SliceParser;
+
+    // Int array declaring 2^30 elements
+    let huge: &[u8] = &[11, 0, 0, 0x40, 0, 0, 0, 1, 2, 3, 4];
+    assert!(matches!(SliceParser::new(huge).allow_any_root(true).parse_named(), Err(ParseError::UnexpectedEndOfInput)));
+
+    let unknown: &[u8] = &[10, 0, 0, 1, 0, 1, b'x', 5, 13];
+    assert_eq!(SliceParser::new(unknown).parse_named().unwrap_err().unknown_tag(), Some(13));
+
+    let nested: &[u8] = &[9, 0, 0, 9, 0, 0, 0, 1, 9, 0, 0, 0, 1, 0, 0, 0, 0, 0];
+    assert!(matches!(SliceParser::new(nested).allow_any_root(true).max_depth(2).parse_named(), Err(ParseError::DepthLimitExceeded(2))));
+    assert!(SliceParser::new(nested).allow_any_root(true).max_depth(3).parse_named().is_ok());
+}
+
+#[test]
+fn parse_borrowed_value_ref() {
+    use std::borrow::Cow;
+
+    use raw_nbt::decode::SliceParser;
+    use raw_nbt::ValueRef;
+
+    let bytes: &[u8] = &[
+        10, 0, 1, b'r', //
+        8, 0, 1, b's', 0, 2, b'h', b'i', //
+        8, 0, 1, b'n', 0, 2, 0xC0, 0x80, //
+        7, 0, 1, b'b', 0, 0, 0, 2, 1, 0xFF, //
+        11, 0, 1, b'i', 0, 0, 0, 1, 0, 0, 1, 0, //
+        0,
+    ];
+
+    let (name, value) = SliceParser::new(bytes).parse_named_ref().unwrap();
+    assert_eq!(name, "r");
+    assert!(matches!(value.get("s").unwrap(), ValueRef::Str(Cow::Borrowed("hi"))));
+    assert!(matches!(value.get("n").unwrap(), ValueRef::Str(Cow::Owned(s)) if s == "\0"));
+    assert_eq!(value.get("b").unwrap().byte_array().unwrap(), [1, -1]);
+    assert_eq!(value.get("i").unwrap().int_array().unwrap(), [256]);
+    assert!(value.get("s").unwrap().int().is_err());
+
+    assert_eq!(value.to_owned(), from_slice_named(bytes).unwrap().1);
+    assert_eq!(value.into_owned()["s"], Value::Str("hi".to_string()));
+}
+
+#[test]
+fn value_ref_list_accessors() {
+    use std::borrow::Cow;
+
+    use raw_nbt::decode::SliceParser;
+    use raw_nbt::ValueRef;
+
+    let bytes: &[u8] = &[
+        10, 0, 0, //
+        9, 0, 3, b'P', b'o', b's', 6, 0, 0, 0, 2, 0x3F, 0xF0, 0, 0, 0, 0, 0, 0, 0x40, 0, 0, 0, 0, 0, 0, 0, //
+        9, 0, 1, b't', 8, 0, 0, 0, 2, 0, 1, b'a', 0, 2, b'b', b'c', //
+        9, 0, 1, b'b', 1, 0, 0, 0, 2, 1, 0xFF, //
+        9, 0, 1, b'l', 9, 0, 0, 0, 1, 3, 0, 0, 0, 1, 0, 0, 0, 7, //
+        9, 0, 1, b'e', 0, 0, 0, 0, 0, //
+        0,
+    ];
+
+    let (_, value) = SliceParser::new(bytes).parse_named_ref().unwrap();
+    assert_eq!(value.get("Pos").unwrap().double_list().unwrap(), [1.0, 2.0]);
+    assert_eq!(value.get("Pos").unwrap().len(), Some(2));
+    assert!(matches!(value.get("t").unwrap().str_list().unwrap()[1], Cow::Borrowed("bc")));
+    assert_eq!(value.get("l").unwrap().list_list().unwrap()[0].int_list().unwrap(), [7]);
+    assert!(value.get("e").unwrap().int_list().unwrap().is_empty());
+    assert_eq!(value.get("e").unwrap().is_empty(), Some(true));
+    assert!(matches!(value.get("e").unwrap().list_get(0), Err(raw_nbt::Error::IndexOutOfRange(0, 0))));
+
+    assert_eq!(*value.get("t").unwrap().list_get(0).unwrap(), ValueRef::Str(Cow::Borrowed("a")));
+    assert!(value.get("Pos").unwrap().str_list().is_err());
+    assert_eq!(value.len(), Some(5));
+
+    // Byte lists outlive the value.
+    let b = value.get("b").unwrap().byte_list().unwrap();
+    drop(value);
+    assert_eq!(b, [1, -1]);
+}
+
+#[test]
+fn bulk_array_reads() {
+    let longs: Vec<i64> = (0..1500).map(|i| i * 0x0102_0304_0506 - 7).collect();
+    let floats: Vec<f32> = (0..3000).map(|i| i as f32 * -0.25).collect();
+
+    for &le in &[false, true] {
+        let be_or_le4 = |x: u32| if le { x.to_le_bytes() } else { x.to_be_bytes() };
+        let name_len: &[u8] = if le { &[1, 0] } else { &[0, 1] };
+        let mut bytes = vec![10, 0, 0, 12];
+        bytes.extend_from_slice(name_len);
+        bytes.push(b'l');
+        bytes.extend_from_slice(&be_or_le4(longs.len() as u32));
+        for x in &longs {
+            bytes.extend_from_slice(&if le { x.to_le_bytes() } else { x.to_be_bytes() });
+        }
+        bytes.push(9);
+        bytes.extend_from_slice(name_len);
+        bytes.extend_from_slice(&[b'f', 5]);
+        bytes.extend_from_slice(&be_or_le4(floats.len() as u32));
+        for x in &floats {
+            bytes.extend_from_slice(&be_or_le4(x.to_bits()));
+        }
+        bytes.push(0);
+
+        let root = if le {
+            Parser::new_le(bytes.as_slice()).parse_named().unwrap().1
+        } else {
+            Parser::new(bytes.as_slice()).parse_named().unwrap().1
+        };
+        assert_eq!(root["l"], Value::LongArray(longs.clone()));
+        assert_eq!(root["f"], Value::FloatList(floats.clone()));
+    }
+}
+
+#[test]
+fn reserve_declared_length() {
+    let mut bytes = vec![11, 0, 1, b'a', 0, 0, 0x27, 0x10];
+    bytes.extend((0..10000).flat_map(|i: i32| i.to_be_bytes()));
+
+    let (_, value) = Parser::new(bytes.as_slice()).allow_any_root(true).parse_named().unwrap();
+    let xs = value.int_array().unwrap();
+    assert_eq!(xs.len(), 10000);
+    assert_eq!(xs.capacity(), 10000);
+}
@@ -0,0 +1,42 @@
+use raw_nbt::decode::from_snbt;
+use raw_nbt::diff::{diff, Change, ChangeKind};
+use raw_nbt::Value;
+
+#[test]
+fn diff_compounds() {
+    let old = from_snbt("{Data:{GameType:0,Time:10L,Player:{Health:20.0f}}}").unwrap();
+    let new = from_snbt("{Data:{GameType:1,Difficulty:2b,Player:{Health:20.0f}}}").unwrap();
+
+    assert_eq!(
+        diff(&old, &new),
+        vec![
+            Change {
+                path: "Data.Difficulty".to_string(),
+                kind: ChangeKind::Added(&Value::Byte(2)),
+            },
+            Change {
+                path: "Data.GameType".to_string(),
+                kind: ChangeKind::Modified(&Value::Int(0), &Value::Int(1)),
+            },
+            Change {
+                path: "Data.Time".to_string(),
+                kind: ChangeKind::Removed(&Value::Long(10)),
+            },
+        ]
+    );
+    assert!(diff(&old, &old).is_empty());
+}
+
+#[test]
+fn diff_lists() {
+    let old = from_snbt("{Items:[{id:\"a\"},{id:\"b\"}],Pos:[1.0d,2.0d],Tags:[]}").unwrap();
+    let new = from_snbt("{Items:[{id:\"a\"},{id:\"c\"}],Pos:[1.0d],Tags:[]}").unwrap();
+
+    let changes = diff(&old, &new);
+    let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+    assert_eq!(paths, ["Items[1].id", "Pos"]);
+
+    let typed_empty = Value::Compound(vec![("Tags".to_string(), Value::IntList(Vec::new()))].into_iter().collect());
+    let end_empty = Value::Compound(vec![("Tags".to_string(), Value::EndList)].into_iter().collect());
+    assert!(diff(&typed_empty, &end_empty).is_empty());
+}
@@ -0,0 +1,165 @@
+#![allow(clippy::four_forward_slashes)]
+
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use raw_nbt::decode::{parse, Parser};
+use raw_nbt::encode::Encoder;
+use raw_nbt::Value;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[test]
+fn roundtrip_idcounts() {
+    let bytes = load("./testdata/idcounts.dat", false).unwrap();
+    assert_eq!(encode(&parse(bytes.as_slice()).unwrap()).unwrap(), bytes);
+}
+
+#[test]
+fn roundtrip_level() {
+    assert_roundtrip("./testdata/level.dat", true);
+}
+
+#[test]
+fn roundtrip_map() {
+    assert_roundtrip("./testdata/map_9.dat", true);
+}
+
+#[test]
+fn roundtrip_villages() {
+    assert_roundtrip("./testdata/villages.dat", true);
+}
+
+#[test]
+fn empty_list_element_tag_id() {
+    // Compound with empty lists of element tag id 0 (end), 1 (byte), 3 (int), and 10 (compound).
+    let bytes: Vec<u8> = vec![
+        10, 0, 0, //
+        9, 0, 1, b'a', 0, 0, 0, 0, 0, //
+        9, 0, 1, b'b', 1, 0, 0, 0, 0, //
+        9, 0, 1, b'c', 3, 0, 0, 0, 0, //
+        9, 0, 1, b'd', 10, 0, 0, 0, 0, //
+        0,
+    ];
+    let nbt = parse(bytes.as_slice()).unwrap();
+    assert_eq!(nbt[""]["a"], Value::EndList);
+    assert_eq!(nbt[""]["b"], Value::EmptyByteList);
+    assert_eq!(nbt[""]["c"], Value::IntList(Vec::new()));
+    assert_eq!(nbt[""]["d"], Value::CompoundList(Vec::new()));
+    assert_eq!(encode(&nbt).unwrap(), bytes);
+    assert_eq!(nbt[""].serialized_len(""), bytes.len());
+}
+
+#[test]
+fn serialized_len_of_lists() {
+    let values = vec![
+        Value::ByteArrayList(vec![vec![1, 2], vec![]]),
+        Value::StrList(vec!["ab".to_string(), "\u{e9}".to_string()]),
+        Value::ListList(vec![Value::IntList(vec![1, 2]), Value::EndList]),
+        Value::LongArrayList(vec![vec![1; 3]]),
+        Value::DoubleList(vec![0.5; 4]),
+    ];
+
+    for value in values {
+        let mut enc = Encoder::new(Vec::new());
+        enc.write("name", &value).unwrap();
+        assert_eq!(value.serialized_len("name"), enc.into_inner().len(), "{:?}", value);
+    }
+}
+
+#[test]
+fn modified_utf8_string() {
+    let mut enc = Encoder::new(Vec::new());
+    enc.write("s", &Value::Str("a\0\u{1F600}".to_string())).unwrap();
+    let bytes = enc.into_inner();
+    assert_eq!(bytes, [8, 0, 1, b's', 0, 9, b'a', 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+    assert_eq!(Value::Str("a\0\u{1F600}".to_string()).serialized_len("s"), bytes.len());
+
+    let nbt = Parser::new(bytes.as_slice()).allow_any_root(true).parse().unwrap();
+    assert_eq!(nbt["s"], Value::Str("a\0\u{1F600}".to_string()));
+}
+
+#[test]
+fn write_root_to_writer() {
+    let level = parse(load("./testdata/level.dat", true).unwrap().as_slice()).unwrap();
+    let data = &level[""];
+
+    let mut bytes = Vec::new();
+    raw_nbt::encode::to_writer(&mut bytes, "", data).unwrap();
+    assert_eq!(parse(bytes.as_slice()).unwrap(), level);
+}
+
+#[test]
+fn to_writer_buffers_output() {
+    struct CountingWriter(Vec<u8>, usize);
+
+    impl std::io::Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.1 += 1;
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let level = parse(load("./testdata/level.dat", true).unwrap().as_slice()).unwrap();
+
+    let mut w = CountingWriter(Vec::new(), 0);
+    raw_nbt::encode::to_writer(&mut w, "", &level[""]).unwrap();
+    assert_eq!(parse(w.0.as_slice()).unwrap(), level);
+    assert!(w.1 <= w.0.len() / 8192 + 1, "{} writes for {} bytes", w.1, w.0.len());
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn write_root_to_gzip_writer() {
+    let level = parse(load("./testdata/level.dat", true).unwrap().as_slice()).unwrap();
+
+    let mut gzipped = Vec::new();
+    raw_nbt::encode::to_gzip_writer(&mut gzipped, "", &level[""]).unwrap();
+    assert_eq!(&gzipped[..2], &[0x1F, 0x8B]);
+    assert_eq!(parse(GzDecoder::new(gzipped.as_slice())).unwrap(), level);
+}
+
+//// test util ////
+
+fn load(path: &str, gzipped: bool) -> Result<Vec<u8>> {
+    let f = File::open(path)?;
+    let mut bytes = Vec::new();
+
+    if gzipped {
+        GzDecoder::new(f).read_to_end(&mut bytes)?;
+    } else {
+        let mut f = f;
+        f.read_to_end(&mut bytes)?;
+    }
+
+    Ok(bytes)
+}
+
+fn encode(nbt: &Value) -> Result<Vec<u8>> {
+    let mut enc = Encoder::new(Vec::new());
+    for (name, value) in nbt.compound()? {
+        enc.write(name, value)?;
+    }
+    Ok(enc.into_inner())
+}
+
+fn assert_roundtrip(path: &str, gzipped: bool) {
+    let bytes = load(path, gzipped).unwrap();
+    let nbt = parse(bytes.as_slice()).unwrap();
+
+    // Compound keys are sorted, so the first pass may reorder fields.
+    let encoded = encode(&nbt).unwrap();
+    let reparsed = parse(encoded.as_slice()).unwrap();
+    assert_eq!(reparsed, nbt);
+    assert_eq!(encode(&reparsed).unwrap(), encoded);
+
+    let len: usize = nbt.entries().unwrap().map(|(name, value)| value.serialized_len(name)).sum();
+    assert_eq!(len, encoded.len());
+}
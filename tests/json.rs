@@ -0,0 +1,24 @@
+#![cfg(feature = "json")]
+
+use raw_nbt::decode::from_snbt;
+use serde_json::json;
+
+#[test]
+fn into_json() {
+    let value = from_snbt("{a:1b,b:[0.5f,2.0f],c:{d:\"x\"},e:[],f:[I;1,2],g:9007199254740991L,h:[L;-9007199254740993L]}");
+    let j = serde_json::Value::from(value.unwrap());
+
+    assert_eq!(
+        j,
+        json!({
+            "a": 1,
+            "b": [0.5, 2.0],
+            "c": {"d": "x"},
+            "e": [],
+            "f": [1, 2],
+            "g": 9007199254740991i64,
+            "h": ["-9007199254740993L"],
+        })
+    );
+    assert_eq!(serde_json::Value::from(raw_nbt::Value::Double(f64::NAN)), serde_json::Value::Null);
+}
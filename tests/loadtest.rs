@@ -1,3 +1,5 @@
+#![allow(clippy::four_forward_slashes)]
+
 use std::error::Error;
 use std::fs::File;
 use std::io::{BufReader, Read};
@@ -12,7 +14,7 @@ type Result<T> = std::result::Result<T, Box<dyn Error>>;
 fn load_idcounts() {
     match wrapped_load("./testdata/idcounts.dat", false) {
         Ok(_) => {}
-        Err(e) => panic!(e.to_string()),
+        Err(e) => panic!("{}", e),
     }
 }
 
@@ -20,7 +22,7 @@ fn load_idcounts() {
 fn load_level() {
     match wrapped_load("./testdata/level.dat", true) {
         Ok(_) => {}
-        Err(e) => panic!(e.to_string()),
+        Err(e) => panic!("{}", e),
     }
 }
 
@@ -28,7 +30,7 @@ fn load_level() {
 fn load_map() {
     match wrapped_load("./testdata/map_9.dat", true) {
         Ok(_) => {}
-        Err(e) => panic!(e.to_string()),
+        Err(e) => panic!("{}", e),
     }
 }
 
@@ -36,7 +38,76 @@ fn load_map() {
 fn load_villages() {
     match wrapped_load("./testdata/villages.dat", true) {
         Ok(_) => {}
-        Err(e) => panic!(e.to_string()),
+        Err(e) => panic!("{}", e),
+    }
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn load_auto() {
+    use raw_nbt::decode::from_reader_auto;
+
+    let raw = from_reader_auto(File::open("./testdata/idcounts.dat").unwrap()).unwrap();
+    assert_eq!(raw, parse(File::open("./testdata/idcounts.dat").unwrap()).unwrap());
+
+    let gzipped = from_reader_auto(File::open("./testdata/level.dat").unwrap()).unwrap();
+    let level = parse(GzDecoder::new(File::open("./testdata/level.dat").unwrap())).unwrap();
+    assert_eq!(gzipped, level);
+}
+
+#[test]
+fn load_from_file() {
+    use raw_nbt::decode::{from_file, ParseError};
+
+    let raw = from_file("./testdata/idcounts.dat").unwrap();
+    assert_eq!(raw, parse(File::open("./testdata/idcounts.dat").unwrap()).unwrap());
+
+    #[cfg(feature = "compression")]
+    {
+        let level = parse(GzDecoder::new(File::open("./testdata/level.dat").unwrap())).unwrap();
+        assert_eq!(from_file("./testdata/level.dat").unwrap(), level);
+    }
+
+    match from_file("./testdata/missing.dat") {
+        Err(ParseError::ReadError(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn load_auto_zlib() {
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use raw_nbt::decode::from_reader_auto;
+
+    let mut raw = Vec::new();
+    File::open("./testdata/idcounts.dat").unwrap().read_to_end(&mut raw).unwrap();
+    let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(&raw).unwrap();
+    let zlib = enc.finish().unwrap();
+
+    assert_eq!(from_reader_auto(zlib.as_slice()).unwrap(), parse(raw.as_slice()).unwrap());
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn load_auto_limited() {
+    use raw_nbt::decode::{from_reader_auto_limited, ParseError};
+
+    // level.dat is 16215 bytes after decompression.
+    let open = || File::open("./testdata/level.dat").unwrap();
+    let level = parse(GzDecoder::new(open())).unwrap();
+    assert_eq!(from_reader_auto_limited(open(), 16215).unwrap(), level);
+    match from_reader_auto_limited(open(), 16214) {
+        Err(ParseError::DecompressedSizeExceeded(16214)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match from_reader_auto_limited(File::open("./testdata/idcounts.dat").unwrap(), 10) {
+        Err(ParseError::DecompressedSizeExceeded(10)) => {}
+        r => panic!("unexpected result: {:?}", r),
     }
 }
 
@@ -57,10 +128,48 @@ fn wrapped_load(path: &str, gzipped: bool) -> Result<()> {
 fn assert_parse<R: Read>(r: R) -> Result<()> {
     match parse(r) {
         Ok(nbt) => {
-            eprintln!("{:?}", nbt);
+            eprintln!("{:#}", nbt.summary(16));
         }
         Err(e) => return Err(Box::new(e)),
     }
 
     Ok(())
 }
+
+#[test]
+fn find_in_level() {
+    use raw_nbt::decode::Parser;
+
+    let f = GzDecoder::new(File::open("./testdata/level.dat").unwrap());
+    let data = Parser::new(f).find("Data").unwrap().unwrap();
+
+    let level = parse(GzDecoder::new(File::open("./testdata/level.dat").unwrap())).unwrap();
+    assert_eq!(&data, level.path(".Data").unwrap());
+
+    // Skipping the whole Data compound must end exactly at the root's end tag.
+    let f = GzDecoder::new(File::open("./testdata/level.dat").unwrap());
+    let mut parser = Parser::new(f);
+    assert_eq!(parser.find("Missing").unwrap(), None);
+    assert_eq!(parser.offset(), 16215);
+}
+
+#[test]
+fn level_summary() {
+    use raw_nbt::decode::{read_level_summary, LevelSummary};
+
+    let summary = read_level_summary(GzDecoder::new(File::open("./testdata/level.dat").unwrap())).unwrap();
+    assert_eq!(
+        summary,
+        LevelSummary {
+            level_name: Some("Main World".to_string()),
+            version_name: Some("1.13.2".to_string()),
+            data_version: Some(1631),
+            last_played: Some(1549125142686),
+            game_type: Some(0),
+            hardcore: Some(false),
+        }
+    );
+
+    let empty = read_level_summary(File::open("./testdata/idcounts.dat").unwrap()).unwrap();
+    assert_eq!(empty, LevelSummary::default());
+}
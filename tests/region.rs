@@ -0,0 +1,77 @@
+#![cfg(feature = "compression")]
+
+use std::io::{Cursor, Write};
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use raw_nbt::decode::from_slice_named;
+use raw_nbt::region::{RegionReader, RegionWriteError, RegionWriter};
+use raw_nbt::Value;
+
+const CHUNK: &[u8] = &[10, 0, 0, 3, 0, 4, b'x', b'P', b'o', b's', 0, 0, 0, 7, 0];
+
+fn region() -> Vec<u8> {
+    let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(CHUNK).unwrap();
+    let compressed = enc.finish().unwrap();
+
+    let mut bytes = vec![0u8; 3 * 4096];
+    // Chunk (1, 2) at sector 2, 1 sector long.
+    let index = 1 + 2 * 32;
+    bytes[index * 4..index * 4 + 4].copy_from_slice(&[0, 0, 2, 1]);
+    bytes[4096 + index * 4..4096 + index * 4 + 4].copy_from_slice(&1_600_000_000u32.to_be_bytes());
+    bytes[2 * 4096..2 * 4096 + 4].copy_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+    bytes[2 * 4096 + 4] = 2;
+    bytes[2 * 4096 + 5..2 * 4096 + 5 + compressed.len()].copy_from_slice(&compressed);
+    bytes
+}
+
+#[test]
+fn read_chunk() {
+    let mut reader = RegionReader::new(Cursor::new(region())).unwrap();
+
+    assert_eq!(reader.chunk(1, 2).unwrap(), Some(from_slice_named(CHUNK).unwrap().1));
+    assert_eq!(reader.timestamp(1, 2), 1_600_000_000);
+    assert_eq!(reader.chunk(0, 0).unwrap(), None);
+    assert_eq!(reader.timestamp(0, 0), 0);
+}
+
+#[test]
+fn rewrite_region() {
+    let mut reader = RegionReader::new(Cursor::new(region())).unwrap();
+    assert_eq!(reader.raw_chunk(0, 0).unwrap(), None);
+    assert_eq!(reader.raw_chunk(1, 2).unwrap().unwrap().0, 2);
+
+    // Grow chunk (1, 2) over one sector and add a chunk after it.
+    let mut writer = RegionWriter::from_reader(&mut reader, Cursor::new(Vec::new())).unwrap();
+    let big = Value::LongArray((0..2000i64).map(|i| i.wrapping_mul(0x5851_F42D_4C95_7F2D)).collect());
+    let mut grown = from_slice_named(CHUNK).unwrap().1;
+    grown.insert("Data", big).unwrap();
+    writer.set_chunk(1, 2, &grown, 1_700_000_000).unwrap();
+    writer.set_chunk(31, 31, &from_slice_named(CHUNK).unwrap().1, 5).unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    assert_eq!(bytes.len() % 4096, 0);
+    assert!(bytes.len() > 5 * 4096);
+
+    let mut reader = RegionReader::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(reader.chunk(1, 2).unwrap(), Some(grown));
+    assert_eq!(reader.timestamp(1, 2), 1_700_000_000);
+    assert_eq!(reader.chunk(31, 31).unwrap(), Some(from_slice_named(CHUNK).unwrap().1));
+    assert_eq!(reader.timestamp(31, 31), 5);
+
+    // Removing a chunk compacts the rest.
+    let mut writer = RegionWriter::from_reader(&mut reader, Cursor::new(Vec::new())).unwrap();
+    writer.remove_chunk(1, 2);
+    let bytes = writer.finish().unwrap().into_inner();
+    assert_eq!(bytes.len(), 3 * 4096);
+    let mut reader = RegionReader::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(reader.chunk(1, 2).unwrap(), None);
+    assert_eq!(reader.chunk(31, 31).unwrap(), Some(from_slice_named(CHUNK).unwrap().1));
+
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    match writer.set_raw_chunk(0, 0, 3, vec![0; 255 * 4096], 0) {
+        Err(RegionWriteError::ChunkTooLarge(n)) => assert_eq!(n, 255 * 4096),
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
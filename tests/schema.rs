@@ -0,0 +1,46 @@
+use raw_nbt::decode::from_snbt;
+use raw_nbt::schema::{validate, Schema, SchemaError};
+use raw_nbt::TagType;
+
+fn player_schema() -> Schema {
+    Schema::new()
+        .field("Health", TagType::Float)
+        .field("Inventory", TagType::List)
+        .compound("abilities", Schema::new().field("flying", TagType::Byte).field("walkSpeed", TagType::Float))
+}
+
+#[test]
+fn validate_ok() {
+    let player = from_snbt("{Health:20.0f,Inventory:[],abilities:{flying:0b,walkSpeed:0.1f},extra:1}").unwrap();
+    assert_eq!(validate(&player, &player_schema()), Ok(()));
+}
+
+#[test]
+fn validate_collects_all_errors() {
+    let player = from_snbt("{Health:20.0d,abilities:{flying:1s}}").unwrap();
+    let errors = validate(&player, &player_schema()).unwrap_err();
+
+    let error = |path: &str, expected, actual| SchemaError {
+        path: path.to_string(),
+        expected,
+        actual,
+    };
+    assert_eq!(
+        errors,
+        [
+            error("Health", TagType::Float, Some(TagType::Double)),
+            error("Inventory", TagType::List, None),
+            error("abilities.flying", TagType::Byte, Some(TagType::Short)),
+            error("abilities.walkSpeed", TagType::Float, None),
+        ]
+    );
+    assert_eq!(errors[0].to_string(), "'Health': wanted Float but actual Double");
+}
+
+#[test]
+fn validate_non_compound() {
+    let schema = Schema::new().compound("abilities", Schema::new());
+    let errors = validate(&from_snbt("{abilities:1b}").unwrap(), &schema).unwrap_err();
+    assert_eq!(errors[0].path, "abilities");
+    assert_eq!((errors[0].expected, errors[0].actual), (TagType::Compound, Some(TagType::Byte)));
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "serde")]
+
+use raw_nbt::{Compound, Value};
+
+#[test]
+fn serialize_json() {
+    let mut c = Compound::new();
+    c.insert("Health".to_string(), Value::Float(20.0));
+    c.insert("Pos".to_string(), Value::DoubleList(vec![0.5, 64.0]));
+    c.insert("Time".to_string(), Value::Long(1 << 40));
+    c.insert("Items".to_string(), Value::EmptyByteList);
+    c.insert("Tags".to_string(), Value::EndList);
+    c.insert("id".to_string(), Value::Str("minecraft:pig".to_string()));
+
+    let json = serde_json::to_string(&Value::Compound(c)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"Health":20.0,"Items":[],"Pos":[0.5,64.0],"Tags":[],"Time":1099511627776,"id":"minecraft:pig"}"#
+    );
+}
+
+#[test]
+fn deserialize_json() {
+    let value: Value = serde_json::from_str(
+        r#"{"a":1,"b":3000000000,"c":1.5,"d":"x","e":[],"f":[1,2.5],"g":[1,3000000000],"h":[{"x":true}]}"#,
+    )
+    .unwrap();
+
+    let mut x = Compound::new();
+    x.insert("x".to_string(), Value::Byte(1));
+    let mut c = Compound::new();
+    c.insert("a".to_string(), Value::Int(1));
+    c.insert("b".to_string(), Value::Long(3_000_000_000));
+    c.insert("c".to_string(), Value::Double(1.5));
+    c.insert("d".to_string(), Value::Str("x".to_string()));
+    c.insert("e".to_string(), Value::EndList);
+    c.insert("f".to_string(), Value::DoubleList(vec![1.0, 2.5]));
+    c.insert("g".to_string(), Value::LongList(vec![1, 3_000_000_000]));
+    c.insert("h".to_string(), Value::CompoundList(vec![x]));
+    assert_eq!(value, Value::Compound(c));
+
+    assert!(serde_json::from_str::<Value>(r#"[1,"a"]"#).is_err());
+}
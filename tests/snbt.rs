@@ -0,0 +1,201 @@
+use raw_nbt::decode::{from_snbt, ParseError};
+use raw_nbt::{Compound, Error, NonFinite, SnbtOptions, TagType, Value};
+
+fn sample() -> Value {
+    let mut item = Compound::new();
+    item.insert("id".to_string(), Value::Str("minecraft:stone".to_string()));
+    item.insert("Count".to_string(), Value::Byte(1));
+
+    let mut c = Compound::new();
+    c.insert("Health".to_string(), Value::Float(20.0));
+    c.insert("Pos".to_string(), Value::DoubleList(vec![0.0, 64.0, 0.0]));
+    c.insert("UUID".to_string(), Value::IntArray(vec![1, -2]));
+    c.insert("Items".to_string(), Value::CompoundList(vec![item]));
+    c.insert("custom name".to_string(), Value::Str("say \"hi\"".to_string()));
+    c.insert("Time".to_string(), Value::Long(12));
+    c.insert("Empty".to_string(), Value::EndList);
+    Value::Compound(c)
+}
+
+#[test]
+fn display_snbt() {
+    assert_eq!(
+        sample().to_string(),
+        r#"{Empty:[],Health:20.0f,Items:[{Count:1b,id:"minecraft:stone"}],Pos:[0.0d,64.0d,0.0d],Time:12L,UUID:[I;1,-2],"custom name":"say \"hi\""}"#
+    );
+}
+
+#[test]
+fn display_snbt_pretty() {
+    let expected = r#"{
+    Empty: [],
+    Health: 20.0f,
+    Items: [
+        {
+            Count: 1b,
+            id: "minecraft:stone"
+        }
+    ],
+    Pos: [0.0d, 64.0d, 0.0d],
+    Time: 12L,
+    UUID: [I;1, -2],
+    "custom name": "say \"hi\""
+}"#;
+    assert_eq!(format!("{:#}", sample()), expected);
+}
+
+#[test]
+fn write_snbt_into_buffer() {
+    let mut buf = String::from("data merge entity @s ");
+    sample().write_snbt(&mut buf, false).unwrap();
+    assert_eq!(buf, format!("data merge entity @s {}", sample()));
+
+    let mut pretty = String::new();
+    sample().write_snbt(&mut pretty, true).unwrap();
+    assert_eq!(pretty, format!("{:#}", sample()));
+}
+
+#[test]
+fn display_summary() {
+    let mut c = Compound::new();
+    c.insert("Data".to_string(), Value::IntArray(vec![0; 4096]));
+    c.insert("Pos".to_string(), Value::DoubleList(vec![0.0, 1.0]));
+    c.insert("Items".to_string(), Value::CompoundList(vec![Compound::new(); 5]));
+    let value = Value::Compound(c);
+
+    assert_eq!(
+        value.summary(3).to_string(),
+        "{Data:[... 4096 ints ...],Items:[... 5 compounds ...],Pos:[0.0d,1.0d]}"
+    );
+    assert_eq!(
+        format!("{:#}", value.summary(3)),
+        "{\n    Data: [... 4096 ints ...],\n    Items: [... 5 compounds ...],\n    Pos: [0.0d, 1.0d]\n}"
+    );
+    assert_eq!(sample().summary(usize::MAX).to_string(), sample().to_string());
+}
+
+#[test]
+fn parse_snbt() {
+    let value = from_snbt(r#"{Health:20.0f, Pos:[0.0d,64.0d,0.0d], 'custom name':"say \"hi\"", Items:[{id:"minecraft:stone",Count:1b}], UUID:[I;1,-2], Time:12L, Empty:[], Name:bare}"#).unwrap();
+
+    let mut expected = sample();
+    expected.compound_mut().unwrap().insert("Name".to_string(), Value::Str("bare".to_string()));
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn parse_snbt_edge_cases() {
+    assert_eq!(from_snbt("{}").unwrap(), Value::Compound(Compound::new()));
+    assert_eq!(from_snbt("[]").unwrap(), Value::EndList);
+    assert_eq!(from_snbt("[B;]").unwrap(), Value::ByteArray(Vec::new()));
+    assert_eq!(from_snbt("[[1,2],[]]").unwrap(), Value::ListList(vec![Value::IntList(vec![1, 2]), Value::EndList]));
+    assert_eq!(from_snbt("[true,2b]").unwrap(), Value::ByteList(vec![1, 2]));
+    assert_eq!(from_snbt("1.5").unwrap(), Value::Double(1.5));
+    assert_eq!(from_snbt("-3s").unwrap(), Value::Short(-3));
+    assert_eq!(from_snbt(r"'it\'s'").unwrap(), Value::Str("it's".to_string()));
+}
+
+#[test]
+fn parse_snbt_errors() {
+    match from_snbt("[1,2b]") {
+        Err(ParseError::ListTypeMismatch { declared, index, offset }) => {
+            assert_eq!((declared, index, offset), (TagType::Int, 1, 3))
+        }
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match from_snbt("{a:[{},{}, [] ]}") {
+        Err(ParseError::ListTypeMismatch { declared, index, offset }) => {
+            assert_eq!((declared, index, offset), (TagType::Compound, 2, 11))
+        }
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match from_snbt("[I;1,2L]") {
+        Err(ParseError::Parse { offset, .. }) => assert_eq!(offset, 5),
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match from_snbt("{a:1") {
+        Err(ParseError::UnexpectedEndOfInput) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn snbt_roundtrip() {
+    assert_eq!(from_snbt(&sample().to_string()).unwrap(), sample());
+    assert_eq!(from_snbt(&format!("{:#}", sample())).unwrap(), sample());
+}
+
+#[test]
+fn write_snbt_key_order() {
+    let value = from_snbt("{b:1,A:{z:2,y:3},c:4}").unwrap();
+
+    let mut buf = String::new();
+    value.write_snbt_with(&mut buf, SnbtOptions::new()).unwrap();
+    assert_eq!(buf, value.to_string());
+    assert_eq!(buf, "{A:{y:3,z:2},b:1,c:4}");
+
+    let mut buf = String::new();
+    value.write_snbt_with(&mut buf, SnbtOptions::new().key_order(|a, b| b.cmp(a))).unwrap();
+    assert_eq!(buf, "{c:4,b:1,A:{z:2,y:3}}");
+
+    let value = from_snbt("{b:1,C:2}").unwrap();
+    assert_eq!(value.to_string(), "{C:2,b:1}");
+    let mut buf = String::new();
+    let options = SnbtOptions::new().pretty(true).key_order(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+    value.write_snbt_with(&mut buf, options).unwrap();
+    assert_eq!(buf, "{\n    b: 1,\n    C: 2\n}");
+}
+
+#[test]
+fn non_finite_floats() {
+    let value = from_snbt("{a:1.5f,b:[0.5d,2.0d]}").unwrap();
+    let mut nan = value.clone();
+    nan.insert("a", Value::Float(f32::NAN)).unwrap();
+    let mut inf = value.clone();
+    inf.insert("b", Value::DoubleList(vec![0.5, f64::NEG_INFINITY])).unwrap();
+
+    assert_eq!(value.to_snbt(SnbtOptions::new()).unwrap(), value.to_string());
+    match inf.to_snbt(SnbtOptions::new()) {
+        Err(Error::NonFinite(x)) => assert_eq!(x, f64::NEG_INFINITY),
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match nan.to_snbt(SnbtOptions::new()) {
+        Err(Error::NonFinite(x)) => assert!(x.is_nan()),
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let zero = SnbtOptions::new().non_finite(NonFinite::Zero);
+    assert_eq!(nan.to_snbt(zero).unwrap(), "{a:0.0f,b:[0.5d,2.0d]}");
+    assert_eq!(inf.to_snbt(zero).unwrap(), "{a:1.5f,b:[0.5d,0.0d]}");
+
+    let str = SnbtOptions::new().non_finite(NonFinite::Str);
+    assert_eq!(nan.to_snbt(str).unwrap(), r#"{a:"NaN",b:[0.5d,2.0d]}"#);
+    assert_eq!(inf.to_snbt(str).unwrap(), r#"{a:1.5f,b:[0.5d,"-Infinity"]}"#);
+
+    assert_eq!(nan.to_string(), "{a:NaNf,b:[0.5d,2.0d]}");
+    assert_eq!(nan.to_snbt(SnbtOptions::new().non_finite(NonFinite::Raw)).unwrap(), nan.to_string());
+}
+
+#[test]
+fn float_precision() {
+    let value = from_snbt("{Motion:[0.30000000000000004d,-1.0d],h:20.25f,n:3}").unwrap();
+
+    assert_eq!(value.to_string(), "{Motion:[0.30000000000000004d,-1.0d],h:20.25f,n:3}");
+    assert_eq!(format!("{:.2}", value), "{Motion:[0.30d,-1.00d],h:20.25f,n:3}");
+    assert_eq!(format!("{:.0}", Value::Double(2.5)), "2d");
+    assert_eq!(from_snbt(&format!("{:.3}", value)).unwrap()["h"], Value::Float(20.25));
+
+    let options = SnbtOptions::new().precision(1);
+    assert_eq!(value.to_snbt(options).unwrap(), "{Motion:[0.3d,-1.0d],h:20.2f,n:3}");
+}
+
+#[test]
+fn parse_inner_snbt() {
+    let value = from_snbt(r#"{Command:"{Count:1b,id:\"stone\"}",Bad:"{a:",n:1}"#).unwrap();
+
+    let inner = value["Command"].parse_inner_snbt().unwrap();
+    assert_eq!(inner["id"], Value::Str("stone".to_string()));
+    assert_eq!(inner["Count"], Value::Byte(1));
+    assert!(matches!(value["Bad"].parse_inner_snbt(), Err(ParseError::UnexpectedEndOfInput)));
+    assert!(matches!(value["n"].parse_inner_snbt(), Err(ParseError::NotStr(TagType::Int))));
+}
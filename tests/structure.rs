@@ -0,0 +1,48 @@
+use raw_nbt::decode::from_snbt;
+use raw_nbt::structure::{parse_structure, BlockState};
+use raw_nbt::{Error, Value};
+
+#[test]
+fn parse_vanilla_structure() {
+    let value = from_snbt(
+        r#"{DataVersion:3465,size:[2,1,1],palette:[{Name:"minecraft:chest",Properties:{facing:"north",waterlogged:"false"}},{Name:"minecraft:air"}],blocks:[{state:0,pos:[0,0,0],nbt:{id:"minecraft:chest",Items:[]}},{state:1,pos:[1,0,0]}],entities:[]}"#,
+    )
+    .unwrap();
+    let structure = parse_structure(&value).unwrap();
+
+    assert_eq!(structure.data_version, Some(3465));
+    assert_eq!(structure.size, [2, 1, 1]);
+    assert_eq!(structure.palette.len(), 2);
+    assert_eq!(structure.palette[0].properties["facing"], "north");
+    assert_eq!(structure.palette[1], BlockState { name: "minecraft:air".to_string(), properties: Default::default() });
+    assert_eq!(structure.blocks[0].nbt.as_ref().unwrap()["id"], Value::Str("minecraft:chest".to_string()));
+    assert_eq!(structure.blocks[1].pos, [1, 0, 0]);
+    assert_eq!(structure.state_of(&structure.blocks[1]).name, "minecraft:air");
+}
+
+#[test]
+fn parse_structure_variants() {
+    let value = from_snbt(
+        r#"{size:[1,1,1],palettes:[[{Name:"minecraft:oak_planks"}],[{Name:"minecraft:spruce_planks"}]],blocks:[{state:0,pos:[0,0,0]}]}"#,
+    )
+    .unwrap();
+    let structure = parse_structure(&value).unwrap();
+    assert_eq!(structure.data_version, None);
+    assert_eq!(structure.palette[0].name, "minecraft:oak_planks");
+}
+
+#[test]
+fn parse_structure_errors() {
+    let parse = |s: &str| parse_structure(&from_snbt(s).unwrap());
+
+    assert!(matches!(parse("{size:[1,1,1],blocks:[]}"), Err(Error::NotFound(_))));
+    assert!(matches!(parse("{size:[1,1],palette:[],blocks:[]}"), Err(Error::InvalidLength(3, 2))));
+    assert!(matches!(
+        parse(r#"{size:[1,1,1],palette:[{Name:"a"}],blocks:[{state:1,pos:[0,0,0]}]}"#),
+        Err(Error::IndexOutOfRange(1, 1))
+    ));
+    assert!(matches!(
+        parse(r#"{size:[1,1,1],palette:[{Name:"a"}],blocks:[{state:-1,pos:[0,0,0]}]}"#),
+        Err(Error::Overflow(-1, _))
+    ));
+}
@@ -0,0 +1,65 @@
+use std::fs::File;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use raw_nbt::decode::{from_slice, from_snbt};
+use raw_nbt::encode::to_writer;
+use raw_nbt::transform::{transform, Action, TransformError};
+use raw_nbt::{TagType, Value};
+
+#[test]
+fn transform_entries() {
+    let value = from_snbt(r#"{Data:{Player:{Name:"Steve",Health:20.0f},Entities:[{id:"pig",Age:3},{id:"cow"}]}}"#).unwrap();
+    let mut input = Vec::new();
+    to_writer(&mut input, "", &value).unwrap();
+
+    let mut paths = Vec::new();
+    let mut output = Vec::new();
+    transform(input.as_slice(), &mut output, |path, tag| {
+        paths.push((path.to_string(), tag));
+        match path {
+            "Data.Player" => Action::Drop,
+            "Data.Entities[0].Age" => Action::Modify(Box::new(|v| Value::Int(v.int().unwrap() + 1))),
+            "Data.Entities[1].id" => Action::Rename("Id".to_string()),
+            _ => Action::Keep,
+        }
+    })
+    .unwrap();
+
+    assert_eq!(
+        paths,
+        vec![
+            ("".to_string(), TagType::Compound),
+            ("Data".to_string(), TagType::Compound),
+            ("Data.Entities".to_string(), TagType::List),
+            ("Data.Entities[0].Age".to_string(), TagType::Int),
+            ("Data.Entities[0].id".to_string(), TagType::Str),
+            ("Data.Entities[1].id".to_string(), TagType::Str),
+            ("Data.Player".to_string(), TagType::Compound),
+        ]
+    );
+
+    let expected = from_snbt(r#"{Data:{Entities:[{id:"pig",Age:4},{Id:"cow"}]}}"#).unwrap();
+    assert_eq!(from_slice(&output).unwrap()[""], expected);
+}
+
+#[test]
+fn transform_keep_all() {
+    let mut bytes = Vec::new();
+    GzDecoder::new(File::open("./testdata/level.dat").unwrap()).read_to_end(&mut bytes).unwrap();
+
+    let mut output = Vec::new();
+    transform(bytes.as_slice(), &mut output, |_, _| Action::Keep).unwrap();
+    assert_eq!(output, bytes);
+}
+
+#[test]
+fn transform_truncated() {
+    let mut input = Vec::new();
+    to_writer(&mut input, "", &from_snbt("{a:{b:1}}").unwrap()).unwrap();
+    input.truncate(input.len() - 2);
+
+    let res = transform(input.as_slice(), Vec::new(), |_, _| Action::Keep);
+    assert!(matches!(res, Err(TransformError::Parse(_))));
+}
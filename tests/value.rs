@@ -0,0 +1,744 @@
+use std::convert::TryFrom;
+
+use raw_nbt::{Compound, CompoundExt, Error, TagType, Value};
+
+#[test]
+fn mutate_in_place() {
+    let mut value = Value::Compound(Compound::new());
+    value.compound_mut().unwrap().insert("a".to_string(), Value::IntArray(vec![1]));
+    value.compound_mut().unwrap().get_mut("a").unwrap().int_array_mut().unwrap().push(2);
+
+    assert_eq!(value.get("a").unwrap().int_array().unwrap(), &vec![1, 2]);
+    match Value::Int(0).str_mut() {
+        Err(Error::InvalidType("str", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn take_ownership() {
+    assert_eq!(Value::Str("abc".to_string()).into_str().unwrap(), "abc");
+    assert_eq!(Value::IntList(vec![1, 2]).into_int_list().unwrap(), vec![1, 2]);
+    assert_eq!(Value::EmptyByteList.into_compound_list().unwrap(), Vec::new());
+    assert!(Value::Byte(1).into_byte_array().is_err());
+}
+
+#[test]
+fn index_by_name() {
+    let mut inner = Compound::new();
+    inner.insert("b".to_string(), Value::Int(1));
+    let mut root = Compound::new();
+    root.insert("a".to_string(), Value::Compound(inner));
+    let value = Value::Compound(root);
+
+    assert_eq!(value["a"]["b"], Value::Int(1));
+}
+
+#[test]
+#[should_panic(expected = "field not found 'c'")]
+fn index_missing_field() {
+    let _ = &Value::Compound(Compound::new())["c"];
+}
+
+#[test]
+fn lookup_path() {
+    let mut item = Compound::new();
+    item.insert("id".to_string(), Value::Str("minecraft:stone".to_string()));
+    let mut player = Compound::new();
+    player.insert("Health".to_string(), Value::Float(20.0));
+    player.insert("Inventory".to_string(), Value::CompoundList(vec![item]));
+    let mut data = Compound::new();
+    data.insert("Player".to_string(), Value::Compound(player));
+    let mut root = Compound::new();
+    root.insert("Data".to_string(), Value::Compound(data));
+    let value = Value::Compound(root);
+
+    assert_eq!(value.path("Data.Player.Health").unwrap(), &Value::Float(20.0));
+    assert_eq!(value.path("Data.Player.Inventory[0].id").unwrap().str().unwrap(), "minecraft:stone");
+    match value.path("Data.Player.Pos") {
+        Err(Error::NotFound(ref name)) if name == "Pos" => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match value.path("Data.Player.Inventory[1].id") {
+        Err(Error::NotFound(ref name)) if name == "Inventory[1]" => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match value.path("Data.Player.Health.Max") {
+        Err(Error::InvalidType("compound", "float")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn assign_path() {
+    let mut value = Value::Compound(Compound::new());
+
+    assert_eq!(value.set_path("Data.GameType", Value::Int(1), true).unwrap(), None);
+    assert_eq!(value.path("Data.GameType").unwrap(), &Value::Int(1));
+    assert_eq!(value.set_path("Data.GameType", Value::Int(3), false).unwrap(), Some(Value::Int(1)));
+    assert_eq!(value.path("Data.GameType").unwrap(), &Value::Int(3));
+
+    match value.set_path("Data.Player.Health", Value::Float(20.0), false) {
+        Err(Error::NotFound(ref name)) if name == "Player" => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match value.set_path("Data.GameType.Mode", Value::Int(0), true) {
+        Err(Error::InvalidType("compound", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    value.set_path("Data.Items", Value::CompoundList(vec![Compound::new()]), false).unwrap();
+    value.set_path("Data.Items[0].id", Value::Str("minecraft:stone".to_string()), false).unwrap();
+    assert_eq!(value.path("Data.Items[0].id").unwrap().str().unwrap(), "minecraft:stone");
+    match value.set_path("Data.Items[0]", Value::Int(0), false) {
+        Err(Error::InvalidType("compound", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn compound_typed_getters() {
+    let mut item = Compound::new();
+    item.insert("id".to_string(), Value::Str("minecraft:stone".to_string()));
+    let mut c = Compound::new();
+    c.insert("GameType".to_string(), Value::Int(1));
+    c.insert("Items".to_string(), Value::CompoundList(vec![item]));
+    c.insert("Tags".to_string(), Value::EndList);
+
+    assert_eq!(c.get_int("GameType").unwrap(), 1);
+    assert_eq!(c.get_compound_list("Items").unwrap()[0].get_str("id").unwrap(), "minecraft:stone");
+    assert!(c.get_str_list("Tags").unwrap().is_empty());
+    match c.get_long("GameType") {
+        Err(Error::InvalidType("long", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match c.get_int("Missing") {
+        Err(Error::NotFound(ref name)) if name == "Missing" => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn empty_constructors() {
+    assert_eq!(Value::new_compound(), Value::Compound(Compound::new()));
+    assert_eq!(Value::default(), Value::new_compound());
+    assert_eq!(Value::empty_list(), Value::EndList);
+
+    #[derive(Default)]
+    struct Entity {
+        nbt: Value,
+    }
+    assert_eq!(Entity::default().nbt.len(), Some(0));
+}
+
+#[test]
+fn from_primitives() {
+    let mut c = Compound::new();
+    c.insert("x".into(), 1i32.into());
+    c.insert("y".into(), 2.5f64.into());
+    c.insert("id".into(), "minecraft:pig".into());
+
+    assert_eq!(c["x"], Value::Int(1));
+    assert_eq!(c["y"], Value::Double(2.5));
+    assert_eq!(c["id"], Value::Str("minecraft:pig".to_string()));
+    assert_eq!(Value::from(-1i8), Value::Byte(-1));
+}
+
+#[test]
+fn from_collections() {
+    let mut c = Compound::new();
+    c.insert("pos".to_string(), vec![1.5f64, 64.0, -2.0].into());
+    c.insert("tags".to_string(), vec!["a".to_string()].into());
+
+    assert_eq!(c["pos"], Value::DoubleList(vec![1.5, 64.0, -2.0]));
+    assert_eq!(c["tags"], Value::StrList(vec!["a".to_string()]));
+    assert_eq!(Value::from(vec![1i32, 2]), Value::IntList(vec![1, 2]));
+    assert_eq!(Value::from(vec![3i64]), Value::LongList(vec![3]));
+    assert_eq!(Value::from(vec![c.clone()]), Value::CompoundList(vec![c.clone()]));
+    assert_eq!(Value::from(Vec::<i16>::new()), Value::EndList);
+    assert_eq!(Value::from(Vec::<Compound>::new()), Value::EndList);
+    assert_eq!(Value::from(c.clone()), Value::Compound(c));
+}
+
+#[test]
+fn try_from_value() {
+    assert_eq!(i32::try_from(Value::Int(3)).unwrap(), 3);
+    assert_eq!(String::try_from(Value::Str("a".to_string())).unwrap(), "a");
+    assert_eq!(Vec::<i8>::try_from(Value::ByteArray(vec![1, 2])).unwrap(), vec![1, 2]);
+    match i64::try_from(Value::Int(3)) {
+        Err(Error::InvalidType("long", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn tag_type() {
+    assert_eq!(Value::Int(1).tag_type(), TagType::Int);
+    assert_eq!(Value::EmptyByteList.tag_type(), TagType::List);
+    assert_eq!(Value::CompoundList(Vec::new()).tag_type().id(), 9);
+    assert_eq!(TagType::LongArray.id(), 12);
+    for id in 0..=12 {
+        assert_eq!(TagType::from_id(id).unwrap().to_id(), id);
+    }
+    assert_eq!(TagType::from_id(13), None);
+    assert_eq!(u8::from(TagType::Compound), 10);
+}
+
+#[test]
+fn filter_by_type() {
+    let value = raw_nbt::decode::from_snbt("{a:{x:1},b:2,c:[1,2],d:{}}").unwrap();
+    let compounds: Vec<&Value> = value.compound().unwrap().values().filter_map(|v| v.as_type(TagType::Compound)).collect();
+
+    assert_eq!(compounds.len(), 2);
+    assert_eq!(value["c"].as_type(TagType::List), Some(&value["c"]));
+    assert_eq!(value["b"].as_type(TagType::Long), None);
+}
+
+#[test]
+fn predicates() {
+    assert!(Value::EndList.is_list());
+    assert!(Value::CompoundList(Vec::new()).is_list());
+    assert!(!Value::Compound(Compound::new()).is_list());
+    assert!(Value::Double(0.0).is_numeric());
+    assert!(!Value::Str(String::new()).is_numeric());
+    assert!(Value::LongArray(Vec::new()).is_array());
+    assert!(!Value::LongList(Vec::new()).is_array());
+}
+
+#[test]
+fn element_count() {
+    assert_eq!(Value::IntArray(vec![1, 2, 3]).len(), Some(3));
+    assert_eq!(Value::EmptyByteList.len(), Some(0));
+    assert_eq!(Value::Compound(Compound::new()).is_empty(), Some(true));
+    assert_eq!(Value::StrList(vec![String::new()]).is_empty(), Some(false));
+    assert_eq!(Value::Int(1).len(), None);
+    assert_eq!(Value::Str("abc".to_string()).is_empty(), None);
+}
+
+#[test]
+fn byte_as_bool() {
+    assert!(Value::Byte(1).bool().unwrap());
+    assert!(Value::Byte(-1).bool().unwrap());
+    assert!(!Value::Byte(0).bool().unwrap());
+    assert!(Value::Int(1).bool().is_err());
+}
+
+#[test]
+fn byte_array_as_u8() {
+    let value = Value::ByteArray(vec![0, 1, -1, -128, 104, 105]);
+    assert_eq!(value.byte_array_u8().unwrap(), &[0, 1, 255, 128, 104, 105]);
+
+    assert_eq!(Value::ByteArray(b"hi".iter().map(|&b| b as i8).collect()).byte_array_str().unwrap(), "hi");
+    match value.byte_array_str() {
+        Err(Error::InvalidUtf8(e)) => assert_eq!(e.valid_up_to(), 2),
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Value::Int(1).byte_array_u8() {
+        Err(Error::InvalidType("byte array", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn uuid() {
+    let uuid = 0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210u128;
+    let value = Value::from_uuid(uuid);
+
+    assert_eq!(value, Value::IntArray(vec![0x0123_4567, 0x89AB_CDEFu32 as i32, 0xFEDC_BA98u32 as i32, 0x7654_3210]));
+    assert_eq!(value.uuid().unwrap(), uuid);
+    match Value::IntArray(vec![1, 2, 3]).uuid() {
+        Err(Error::InvalidLength(4, 3)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn iterate_entries() {
+    let mut c = Compound::new();
+    c.insert("a".to_string(), Value::Int(1));
+    c.insert("b".to_string(), Value::Int(2));
+    let mut value = Value::Compound(c);
+
+    for (_, v) in value.entries_mut().unwrap() {
+        *v = Value::Int(v.int().unwrap() * 10);
+    }
+    let entries: Vec<_> = value.entries().unwrap().map(|(k, v)| (k.as_str(), v.int().unwrap())).collect();
+    assert_eq!(entries, vec![("a", 10), ("b", 20)]);
+    assert!(Value::IntList(Vec::new()).entries().is_err());
+
+    let owned: Vec<(String, Value)> = value.into_entries().unwrap().collect();
+    assert_eq!(owned, vec![("a".to_string(), Value::Int(10)), ("b".to_string(), Value::Int(20))]);
+    assert!(matches!(Value::Int(1).into_entries(), Err(Error::InvalidType(_, _))));
+}
+
+#[test]
+fn iterate_recursively() {
+    let value = raw_nbt::decode::from_snbt("{a:{b:1},c:[[2,3],[]],d:[{e:4s}]}").unwrap();
+    let visited: Vec<String> = value.iter_recursive().skip(1).map(|v| v.to_string()).collect();
+
+    assert_eq!(visited, ["{b:1}", "1", "[[2,3],[]]", "[2,3]", "[]", "[{e:4s}]", "4s"]);
+}
+
+#[test]
+fn iterate_compounds() {
+    let value = raw_nbt::decode::from_snbt("{a:{b:1},c:[[{x:1}],[]],d:[{e:{f:2}},{}]}").unwrap();
+    let paths: Vec<String> = value.compounds().map(|(path, _)| path).collect();
+    assert_eq!(paths, ["", "a", "c[0][0]", "d[0]", "d[0].e", "d[1]"]);
+
+    let (path, found) = value.compounds().find(|(_, c)| c.contains_key("f")).unwrap();
+    assert_eq!(path, "d[0].e");
+    assert_eq!(found["f"], Value::Int(2));
+    assert_eq!(Value::Int(1).compounds().count(), 0);
+}
+
+#[test]
+fn list_as_values() {
+    assert_eq!(Value::IntList(vec![1, 2]).to_value_list().unwrap(), [Value::Int(1), Value::Int(2)]);
+    assert_eq!(
+        Value::StrList(vec!["a".to_string()]).to_value_list().unwrap(),
+        [Value::Str("a".to_string())]
+    );
+    assert_eq!(
+        Value::CompoundList(vec![Compound::new()]).to_value_list().unwrap(),
+        [Value::new_compound()]
+    );
+    assert!(Value::EmptyByteList.to_value_list().unwrap().is_empty());
+    match Value::IntArray(vec![1]).to_value_list() {
+        Err(Error::InvalidType("list", "int array")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn fold_recursively() {
+    let value = raw_nbt::decode::from_snbt("{a:{b:1},c:[[2,3],[]],d:[{e:4s}]}").unwrap();
+    let visited = value.fold(Vec::new(), |mut acc, v| {
+        acc.push(v.to_string());
+        acc
+    });
+    let iterated: Vec<String> = value.iter_recursive().map(|v| v.to_string()).collect();
+    assert_eq!(visited, iterated);
+
+    let ints = value.fold(0, |n, v| n + v.as_i64().unwrap_or(0));
+    assert_eq!(ints, 5);
+}
+
+#[test]
+fn find_all_keys() {
+    let value = raw_nbt::decode::from_snbt(
+        r#"{id:"player",Inventory:[{id:"stone"},{Count:1b},{id:"chest",tag:{Items:[{id:"dirt"}]}}],l:[[{id:1}]]}"#,
+    )
+    .unwrap();
+    let found: Vec<(String, String)> = value.find_all("id").into_iter().map(|(p, v)| (p, v.to_string())).collect();
+
+    assert_eq!(
+        found,
+        [
+            ("Inventory[0].id".to_string(), r#""stone""#.to_string()),
+            ("Inventory[2].id".to_string(), r#""chest""#.to_string()),
+            ("Inventory[2].tag.Items[0].id".to_string(), r#""dirt""#.to_string()),
+            ("id".to_string(), r#""player""#.to_string()),
+            ("l[0][0].id".to_string(), "1".to_string()),
+        ]
+    );
+    for (path, v) in value.find_all("id") {
+        assert_eq!(value.path(&path).unwrap(), v);
+    }
+}
+
+#[test]
+fn collect_strings() {
+    let value = raw_nbt::decode::from_snbt(
+        r#"{Text1:"hello",book:{pages:["p1","p2"],author:"me"},Items:[{id:"stone",Count:1b}],n:[["a"],[]]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value.collect_strings(),
+        [
+            ("Items[0].id".to_string(), "stone"),
+            ("Text1".to_string(), "hello"),
+            ("book.author".to_string(), "me"),
+            ("book.pages[0]".to_string(), "p1"),
+            ("book.pages[1]".to_string(), "p2"),
+            ("n[0][0]".to_string(), "a"),
+        ]
+    );
+    assert!(Value::Int(1).collect_strings().is_empty());
+}
+
+#[test]
+fn single_element_list() {
+    assert_eq!(*Value::IntList(vec![7]).single().unwrap(), Value::Int(7));
+    assert_eq!(*Value::ListList(vec![Value::EndList]).single().unwrap(), Value::EndList);
+    match Value::IntList(vec![1, 2]).single() {
+        Err(Error::InvalidLength(1, 2)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Value::Int(1).single() {
+        Err(Error::InvalidType("list", "int")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    assert_eq!(Value::StrList(vec!["a".to_string(), "b".to_string()]).list_first().unwrap(), Value::Str("a".to_string()));
+    match Value::EndList.list_first() {
+        Err(Error::NotFound(ref name)) if name == "[0]" => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Value::IntArray(vec![1]).list_first() {
+        Err(Error::InvalidType("list", "int array")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn optional_field() {
+    let value = raw_nbt::decode::from_snbt("{a:1}").unwrap();
+    assert_eq!(value.get_opt("a").unwrap(), Some(&Value::Int(1)));
+    assert_eq!(value.get_opt("b").unwrap(), None);
+    assert_eq!(value.get_opt("b").unwrap().and_then(|v| v.int().ok()).unwrap_or(5), 5);
+    assert!(matches!(Value::Int(1).get_opt("a"), Err(Error::InvalidType("compound", "int"))));
+}
+
+#[test]
+fn flatten_and_homogenize_lists() {
+    let ll = |xs: Vec<Value>| Value::ListList(xs);
+
+    let value = ll(vec![Value::IntList(vec![1, 2]), Value::EndList, Value::IntList(vec![3])]);
+    assert_eq!(value.flatten_list().unwrap(), Value::IntList(vec![1, 2, 3]));
+    assert_eq!(ll(vec![Value::EmptyByteList]).flatten_list().unwrap(), Value::EndList);
+    assert!(matches!(
+        ll(vec![Value::IntList(vec![1]), Value::ShortList(vec![2])]).flatten_list(),
+        Err(Error::InvalidType("int list", "short list"))
+    ));
+    assert!(Value::IntList(vec![1]).flatten_list().is_err());
+
+    let mixed = ll(vec![Value::EmptyByteList, Value::StrList(vec!["a".to_string()]), Value::EndList]);
+    let mut c = Compound::new();
+    c.insert("l".to_string(), mixed);
+    c.insert("e".to_string(), ll(vec![Value::EmptyByteList, Value::IntList(Vec::new())]));
+    let mut expected = Compound::new();
+    expected.insert(
+        "l".to_string(),
+        ll(vec![Value::StrList(Vec::new()), Value::StrList(vec!["a".to_string()]), Value::StrList(Vec::new())]),
+    );
+    expected.insert("e".to_string(), ll(vec![Value::IntList(Vec::new()), Value::IntList(Vec::new())]));
+    assert_eq!(Value::Compound(c).homogenize(), Value::Compound(expected));
+
+    let irregular = ll(vec![Value::IntList(vec![1]), Value::ShortList(vec![2])]);
+    assert_eq!(irregular.homogenize(), irregular);
+}
+
+#[test]
+fn count_tag_types() {
+    let value = raw_nbt::decode::from_snbt("{a:1,b:[1s,2s],c:[[1],[]],d:[{e:1b},{}],f:[I;1,2]}").unwrap();
+    let histogram: Vec<(TagType, usize)> = value.type_histogram().into_iter().collect();
+
+    assert_eq!(
+        histogram,
+        [
+            (TagType::Byte, 1),
+            (TagType::Short, 2),
+            (TagType::Int, 2),
+            (TagType::List, 5),
+            (TagType::Compound, 3),
+            (TagType::IntArray, 1),
+        ]
+    );
+}
+
+#[test]
+fn edit_compound() {
+    let mut value = Value::Compound(Compound::new());
+
+    assert_eq!(value.insert("a", Value::Int(1)).unwrap(), None);
+    assert_eq!(value.insert("a".to_string(), Value::Int(2)).unwrap(), Some(Value::Int(1)));
+    assert!(value.contains_key("a").unwrap());
+    assert_eq!(value.remove("a").unwrap(), Some(Value::Int(2)));
+    assert!(!value.contains_key("a").unwrap());
+    assert!(Value::Int(0).insert("a", Value::Int(1)).is_err());
+}
+
+#[test]
+fn compound_entry() {
+    let mut value = Value::Compound(Compound::new());
+
+    *value
+        .entry("Data")
+        .unwrap()
+        .or_insert(Value::Compound(Compound::new()))
+        .entry("Player")
+        .unwrap()
+        .or_insert(Value::Compound(Compound::new()))
+        .entry("Score")
+        .unwrap()
+        .or_insert(Value::Int(0)) = Value::Int(5);
+    assert_eq!(value.path("Data.Player.Score").unwrap(), &Value::Int(5));
+
+    value.entry("Data").unwrap().or_insert(Value::Int(0)).insert("Time", Value::Long(1)).unwrap();
+    assert_eq!(value.path("Data.Time").unwrap(), &Value::Long(1));
+
+    match Value::Int(1).entry("x") {
+        Err(Error::InvalidType("compound", "int")) => {}
+        r => panic!("unexpected result: {:?}", r.map(|e| e.key().clone())),
+    }
+}
+
+#[test]
+fn merge_compounds() {
+    use raw_nbt::decode::from_snbt;
+    use raw_nbt::{merge, ListMerge};
+
+    let base = from_snbt("{a:1,b:{c:2,d:3},l:[1,2],e:[],m:[1b]}").unwrap().into_compound().unwrap();
+    let overlay = from_snbt("{a:5,b:{d:4,f:6},l:[3],e:[7],m:[2]}").unwrap().into_compound().unwrap();
+
+    let mut replaced = base.clone();
+    merge(&mut replaced, &overlay, ListMerge::Replace);
+    assert_eq!(Value::Compound(replaced), from_snbt("{a:5,b:{c:2,d:4,f:6},l:[3],e:[7],m:[2]}").unwrap());
+
+    let mut concatenated = base;
+    merge(&mut concatenated, &overlay, ListMerge::Concat);
+    assert_eq!(Value::Compound(concatenated), from_snbt("{a:5,b:{c:2,d:4,f:6},l:[1,2,3],e:[7],m:[2]}").unwrap());
+}
+
+#[test]
+fn compound_builder() {
+    use raw_nbt::decode::from_snbt;
+    use raw_nbt::CompoundBuilder;
+
+    let pig = CompoundBuilder::new()
+        .byte("OnGround", 1)
+        .string("id", "minecraft:pig")
+        .list("Pos", vec![0.5, 64.0, -2.5])
+        .list("Tags", Vec::<String>::new())
+        .compound("Brain", CompoundBuilder::new().value("memories", Value::Compound(Compound::new())))
+        .int_array("UUID", vec![1, 2, 3, 4])
+        .build();
+    let expected = from_snbt("{OnGround:1b,id:\"minecraft:pig\",Pos:[0.5d,64.0d,-2.5d],Tags:[],Brain:{memories:{}},UUID:[I;1,2,3,4]}");
+    assert_eq!(pig, expected.unwrap());
+}
+
+#[test]
+fn make_typed_list() {
+    assert_eq!(Value::make_list(vec![Value::Int(1), Value::Int(2)]).unwrap(), Value::IntList(vec![1, 2]));
+    assert_eq!(Value::make_list(vec![Value::Byte(1)]).unwrap(), Value::ByteList(vec![1]));
+    assert_eq!(Value::make_list(Vec::new()).unwrap(), Value::EndList);
+    assert_eq!(
+        Value::make_list(vec![Value::EndList, Value::IntList(vec![1])]).unwrap(),
+        Value::ListList(vec![Value::EndList, Value::IntList(vec![1])])
+    );
+
+    match Value::make_list(vec![Value::Int(1), Value::Int(2), Value::Str("a".to_string())]) {
+        Err(Error::ListTypeMismatch {
+            declared: TagType::Int,
+            index: 2,
+            found: TagType::Str,
+        }) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+#[should_panic(expected = "mismatched element type at index 1")]
+fn compound_builder_mixed_list() {
+    raw_nbt::CompoundBuilder::new().list("xs", vec![Value::Int(1), Value::Byte(2)]);
+}
+
+#[test]
+fn coerce_empty_list() {
+    assert_eq!(Value::EndList.as_empty_list(), Some(()));
+    assert_eq!(Value::EmptyByteList.as_empty_list(), Some(()));
+    assert_eq!(Value::StrList(Vec::new()).as_empty_list(), Some(()));
+    assert_eq!(Value::IntList(vec![1]).as_empty_list(), None);
+    assert_eq!(Value::Int(0).as_empty_list(), None);
+
+    assert_eq!(Value::EmptyByteList.coerce_list(TagType::Int).unwrap(), Value::IntList(Vec::new()));
+    assert_eq!(Value::EndList.coerce_list(TagType::Compound).unwrap(), Value::CompoundList(Vec::new()));
+    assert_eq!(Value::IntList(vec![1]).coerce_list(TagType::Int).unwrap(), Value::IntList(vec![1]));
+    match Value::IntList(vec![1]).coerce_list(TagType::Str) {
+        Err(Error::InvalidType("str list", "int list")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn convert_numeric_lists() {
+    assert_eq!(Value::IntList(vec![1, -2]).widen_numeric_list().unwrap(), Value::LongList(vec![1, -2]));
+    assert_eq!(Value::ByteList(vec![3]).widen_numeric_list().unwrap(), Value::ShortList(vec![3]));
+    assert_eq!(Value::FloatList(vec![0.5]).widen_numeric_list().unwrap(), Value::DoubleList(vec![0.5]));
+    assert_eq!(Value::EmptyByteList.widen_numeric_list().unwrap(), Value::ShortList(Vec::new()));
+    match Value::LongList(vec![1]).widen_numeric_list() {
+        Err(Error::InvalidType(_, "long list")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+
+    let ints = Value::IntList(vec![1, 300]);
+    assert_eq!(ints.coerce_list_to(TagType::Short).unwrap(), Value::ShortList(vec![1, 300]));
+    assert_eq!(ints.coerce_list_to(TagType::Double).unwrap(), Value::DoubleList(vec![1.0, 300.0]));
+    match ints.coerce_list_to(TagType::Byte) {
+        Err(Error::Overflow(300, "byte")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match ints.coerce_list_to(TagType::Float) {
+        Err(Error::InvalidType("float list", "int list")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Value::StrList(vec!["1".to_string()]).coerce_list_to(TagType::Int) {
+        Err(Error::InvalidType("int list", "str list")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    assert_eq!(Value::EndList.coerce_list_to(TagType::Long).unwrap(), Value::LongList(Vec::new()));
+}
+
+#[test]
+fn list_element_type() {
+    assert_eq!(Value::IntList(vec![1]).list_element_type(), Some(TagType::Int));
+    assert_eq!(Value::CompoundList(Vec::new()).list_element_type(), Some(TagType::Compound));
+    assert_eq!(Value::ListList(vec![Value::EndList]).list_element_type(), Some(TagType::List));
+    assert_eq!(Value::EndList.list_element_type(), None);
+    assert_eq!(Value::EmptyByteList.list_element_type(), None);
+    assert_eq!(Value::IntArray(vec![1]).list_element_type(), None);
+}
+
+#[test]
+fn index_mut_by_name() {
+    let mut value = raw_nbt::decode::from_snbt("{Data:{version:1}}").unwrap();
+    value["Data"]["version"] = Value::Int(19133);
+    assert_eq!(value.path("Data.version").unwrap(), &Value::Int(19133));
+}
+
+#[test]
+#[should_panic(expected = "field not found 'missing'")]
+fn index_mut_missing_field() {
+    Value::Compound(Compound::new())["missing"] = Value::Int(1);
+}
+
+#[test]
+fn numeric_coercion() {
+    assert_eq!(Value::Byte(-1).as_i64().unwrap(), -1);
+    assert_eq!(Value::Short(300).as_i64().unwrap(), 300);
+    assert_eq!(Value::Int(70000).as_i64().unwrap(), 70000);
+    assert_eq!(Value::Long(1 << 40).as_i64().unwrap(), 1 << 40);
+    assert!(Value::Float(1.0).as_i64().is_err());
+
+    assert_eq!(Value::Float(0.5).as_f64().unwrap(), 0.5);
+    assert_eq!(Value::Double(-2.25).as_f64().unwrap(), -2.25);
+    assert_eq!(Value::Int(3).as_f64().unwrap(), 3.0);
+    match Value::Str("1".to_string()).as_f64() {
+        Err(Error::InvalidType("number", "str")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn checked_downcast() {
+    assert_eq!(Value::Long(70000).as_i32_checked().unwrap(), 70000);
+    assert_eq!(Value::Byte(-5).as_i32_checked().unwrap(), -5);
+    assert_eq!(Value::Int(-32768).as_i16_checked().unwrap(), -32768);
+    assert_eq!(Value::Long(127).as_i8_checked().unwrap(), 127);
+
+    match Value::Long(1 << 31).as_i32_checked() {
+        Err(Error::Overflow(x, "int")) => assert_eq!(x, 1 << 31),
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Value::Short(128).as_i8_checked() {
+        Err(Error::Overflow(128, "byte")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    match Value::Double(1.0).as_i16_checked() {
+        Err(Error::InvalidType("integer", "double")) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn semantic_equality() {
+    use raw_nbt::decode::from_snbt;
+
+    let a = from_snbt("{a:[],b:[{c:[]}],d:[[],[1]]}").unwrap();
+    let mut b = a.clone();
+    b["a"] = Value::EmptyByteList;
+    b["b"] = from_snbt("[{c:[\"x\"]}]").unwrap();
+    assert!(!a.semantic_eq(&b));
+
+    b["b"] = Value::CompoundList(vec![[("c".to_string(), Value::StrList(Vec::new()))].iter().cloned().collect()]);
+    b["d"] = Value::ListList(vec![Value::IntList(Vec::new()), Value::IntList(vec![1])]);
+    assert_ne!(a, b);
+    assert!(a.semantic_eq(&b));
+    assert!(!Value::EndList.semantic_eq(&Value::IntList(vec![0])));
+}
+
+#[test]
+fn retain_recursively() {
+    use raw_nbt::decode::from_snbt;
+
+    let mut value = from_snbt(r#"{id:"chest",ForgeData:{a:1},Items:[{id:"stone",ForgeCaps:{}},{id:"dirt"}],l:[[{ForgeX:1b,y:2}]]}"#)
+        .unwrap();
+    let mut visited = 0;
+    value.retain(|name, _| {
+        visited += 1;
+        !name.starts_with("Forge")
+    });
+
+    assert_eq!(value, from_snbt(r#"{id:"chest",Items:[{id:"stone"},{id:"dirt"}],l:[[{y:2}]]}"#).unwrap());
+    assert_eq!(visited, 9);
+
+    let mut int = Value::Int(1);
+    int.retain(|_, _| false);
+    assert_eq!(int, Value::Int(1));
+}
+
+#[test]
+fn canonical_form() {
+    use raw_nbt::decode::from_snbt;
+    use std::collections::HashSet;
+
+    let a = from_snbt("{a:[],b:[{c:[]}],d:[[],[1]],x:0.0d}").unwrap();
+    let mut b = a.clone();
+    b["a"] = Value::EmptyByteList;
+    b["b"] = Value::CompoundList(vec![[("c".to_string(), Value::StrList(Vec::new()))].iter().cloned().collect()]);
+    b["d"] = Value::ListList(vec![Value::IntList(Vec::new()), Value::IntList(vec![1])]);
+    b["x"] = Value::Double(-0.0);
+
+    assert_ne!(a, b);
+    assert_eq!(a.canonicalize(), b.canonicalize());
+    assert_eq!(b.canonicalize()["a"], Value::EndList);
+    assert_eq!(b.canonicalize()["x"].double().unwrap().to_bits(), 0.0f64.to_bits());
+
+    let set: HashSet<Value> = [a, b].iter().map(Value::canonicalize).collect();
+    assert_eq!(set.len(), 1);
+
+    let nan = Value::FloatList(vec![f32::from_bits(0x7FC0_0001)]).canonicalize();
+    assert_eq!(nan.float_list().unwrap()[0].to_bits(), f32::NAN.to_bits());
+}
+
+#[test]
+fn hash_set_dedup() {
+    use raw_nbt::decode::from_snbt;
+    use std::collections::HashSet;
+
+    let mut set = HashSet::new();
+    set.insert(from_snbt("{id:\"chest\",Items:[],x:1.5f}").unwrap());
+    set.insert(from_snbt("{x:1.5f,Items:[],id:\"chest\"}").unwrap());
+    set.insert(from_snbt("{id:\"chest\",Items:[],x:2.5f}").unwrap());
+    set.insert(Value::Double(0.0));
+    set.insert(Value::Double(-0.0));
+    assert_eq!(set.len(), 3);
+}
+
+#[test]
+fn list_get_element() {
+    use std::borrow::Cow;
+
+    let value = raw_nbt::decode::from_snbt("{xs:[1s,2s],ls:[[1],[2]],cs:[{id:\"a\"},{id:\"b\"}]}").unwrap();
+    assert_eq!(*value["xs"].list_get(1).unwrap(), Value::Short(2));
+    assert!(matches!(value["ls"].list_get(0).unwrap(), Cow::Borrowed(Value::IntList(_))));
+    assert_eq!(value["cs"].list_get(1).unwrap().get("id").unwrap(), &Value::Str("b".to_string()));
+
+    match value["xs"].list_get(2) {
+        Err(Error::IndexOutOfRange(2, 2)) => {}
+        r => panic!("unexpected result: {:?}", r),
+    }
+    assert!(matches!(Value::EndList.list_get(0), Err(Error::IndexOutOfRange(0, 0))));
+    assert!(matches!(Value::IntArray(vec![1]).list_get(0), Err(Error::InvalidType("list", "int array"))));
+}